@@ -1,18 +1,70 @@
 pub use clap::Parser;
 use clap::*;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Default, Parser)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Args {
-    #[arg(help = "The file to be compiled")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // the default only exists for `krillion run`, which has its own input
+    #[arg(
+        help = "The file to be compiled",
+        required = true,
+        default_value = "",
+        hide_default_value = true
+    )]
     pub input: String,
 
-    #[arg(short, long, help = "Output file's filename", default_value = "a.out")]
-    pub output: String,
+    #[arg(
+        short,
+        long,
+        help = "Output file's filename, other emitted files are placed next to it [default: a.out]"
+    )]
+    pub output: Option<String>,
+
+    #[arg(
+        long,
+        help = "Stages to be emitted",
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [Emit::Exe]
+    )]
+    pub emit: Vec<Emit>,
+
+    #[arg(short = 'O', help = "Optimization level", value_enum, default_value_t = OptLevel::O0, global = true)]
+    pub opt_level: OptLevel,
+
+    #[arg(short = 'c', help = "Only compile to an object file without linking", action = ArgAction::SetTrue)]
+    pub compile_only: bool,
+
+    #[arg(long, help = "Target triple to compile for [default: host]")]
+    pub target: Option<String>,
+
+    #[arg(
+        long,
+        help = "Target CPU to compile for [default: host, or generic with --target]"
+    )]
+    pub cpu: Option<String>,
+
+    #[arg(long, help = "Target features to enable, e.g. `+avx2,-sse4.1`")]
+    pub target_features: Option<String>,
+
+    #[arg(short = 'g', help = "Generate DWARF debug info", action = ArgAction::SetTrue)]
+    pub debug_info: bool,
 
-    #[arg(long, help = "The style for error reporting", value_enum, default_value_t = ErrorStyle::Normal)]
+    #[arg(
+        long,
+        help = "The linker used to produce executables",
+        default_value = "cc"
+    )]
+    pub linker: String,
+
+    #[arg(long, help = "The style for error reporting", value_enum, default_value_t = ErrorStyle::Normal, global = true)]
     pub error_style: ErrorStyle,
 
-    #[arg(long, help = "Change error message colors for colorblind people", action = ArgAction::SetTrue)]
+    #[arg(long, help = "Change error message colors for colorblind people", action = ArgAction::SetTrue, global = true)]
     pub alt_color: bool,
 
     #[cfg(debug_assertions)]
@@ -20,6 +72,141 @@ pub struct Args {
     pub panics: Option<String>,
 }
 
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Compile and run a file in memory without linking")]
+    Run {
+        #[arg(help = "The file to be run")]
+        input: String,
+
+        #[arg(
+            help = "Arguments passed to the program",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
+}
+
+impl Args {
+    /// Parses the arguments of the process, `krillion run <input>` is treated as if `<input>` was
+    /// given directly
+    pub fn from_env() -> Self {
+        let mut args = Self::parse();
+        if let Some(Command::Run { input, .. }) = &args.command {
+            args.input = input.clone();
+        }
+
+        args
+    }
+
+    pub const fn is_run(&self) -> bool { matches!(self.command, Some(Command::Run { .. })) }
+
+    pub fn emits(&self, e: Emit) -> bool {
+        match e {
+            Emit::Exe => !self.compile_only && !self.is_run() && self.emit.contains(&Emit::Exe),
+            Emit::Obj => {
+                self.emit.contains(&Emit::Obj)
+                    || (self.compile_only && self.emit.contains(&Emit::Exe))
+            },
+            e => self.emit.contains(&e),
+        }
+    }
+
+    /// The last compiler stage that has to be run to emit everything requested
+    pub fn last_stage(&self) -> Emit {
+        let last = Emit::ALL
+            .into_iter()
+            .filter(|e| self.emits(*e))
+            .max()
+            .unwrap_or(Emit::Tokens);
+
+        // running needs the module but nothing written to disk
+        if self.is_run() {
+            last.max(Emit::LlvmIr)
+        } else {
+            last
+        }
+    }
+
+    /// Where an emitted file should be written. If it's the only file to be emitted, the output
+    /// filename is used as is, otherwise the extension is replaced.
+    pub fn artifact(&self, e: Emit) -> PathBuf {
+        let files = Emit::ALL
+            .into_iter()
+            .filter(|e| e.is_file() && self.emits(*e))
+            .count();
+
+        match (&self.output, e.extension()) {
+            (Some(o), _) if files == 1 => PathBuf::from(o),
+            (Some(o), None) => PathBuf::from(o),
+            (Some(o), Some(ext)) => Path::new(o).with_extension(ext),
+            (None, None) => PathBuf::from("a.out"),
+            (None, Some(ext)) => {
+                PathBuf::from(Path::new(&self.input).file_stem().unwrap_or_default())
+                    .with_extension(ext)
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Emit {
+    Tokens,
+    Ast,
+    Nast,
+    Types,
+    LlvmIr,
+    LlvmBc,
+    Asm,
+    Obj,
+    Exe,
+}
+
+impl Emit {
+    const ALL: [Self; 9] = [
+        Self::Tokens,
+        Self::Ast,
+        Self::Nast,
+        Self::Types,
+        Self::LlvmIr,
+        Self::LlvmBc,
+        Self::Asm,
+        Self::Obj,
+        Self::Exe,
+    ];
+
+    /// Stages that are not written to a file are dumped to stdout
+    pub const fn is_file(&self) -> bool {
+        !matches!(self, Self::Tokens | Self::Ast | Self::Nast | Self::Types)
+    }
+
+    pub const fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::LlvmIr => Some("ll"),
+            Self::LlvmBc => Some("bc"),
+            Self::Asm => Some("s"),
+            Self::Obj => Some("o"),
+            Self::Tokens | Self::Ast | Self::Nast | Self::Types | Self::Exe => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OptLevel {
+    #[default]
+    #[value(name = "0")]
+    O0,
+    #[value(name = "1")]
+    O1,
+    #[value(name = "2")]
+    O2,
+    #[value(name = "3")]
+    O3,
+    #[value(name = "s", help = "Optimize for size")]
+    Os,
+}
+
 #[derive(Clone, Debug, Default, ValueEnum)]
 pub enum ErrorStyle {
     #[default]
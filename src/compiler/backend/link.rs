@@ -0,0 +1,17 @@
+use std::path::Path;
+use std::process::Command;
+
+pub fn link(objects: &[&Path], output: &Path, linker: &str) -> Result<(), String> {
+    let status = Command::new(linker)
+        .args(objects)
+        .arg("-o")
+        .arg(output)
+        .status()
+        .map_err(|e| format!("unable to run linker `{linker}`: {e}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("linker `{linker}` failed ({status})"))
+    }
+}
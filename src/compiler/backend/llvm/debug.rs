@@ -0,0 +1,458 @@
+use super::*;
+use inkwell::{
+    debug_info::{
+        AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DIScope, DIType,
+        DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder, debug_metadata_version,
+    },
+    module::{FlagBehavior, Linkage},
+    targets::TargetData,
+};
+use std::cell::RefCell;
+
+// `DW_ATE_*` encodings
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x07;
+const DW_ATE_UTF: u32 = 0x10;
+
+pub(super) struct DebugInfo<'a, 'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    unit: DICompileUnit<'ctx>,
+    scopes: Vec<DIScope<'ctx>>,
+    target: TargetData,
+    sources: &'a SourceMap,
+    /// The debug file of each source file, by its id
+    files: Vec<DIFile<'ctx>>,
+    is_optimized: bool,
+    /// Structs whose debug type is being built, a struct referring to itself through a pointer
+    /// gets an opaque type instead
+    structs: RefCell<Vec<usize>>,
+}
+
+impl<'a, 'ctx> DebugInfo<'a, 'ctx> {
+    pub(super) fn new(
+        context: &'ctx Context,
+        module: &Module<'ctx>,
+        machine: &TargetMachine,
+        sources: &'a SourceMap,
+        args: &'a Args,
+    ) -> Self {
+        let (filename, directory) = split_path(&args.input);
+        let is_optimized = !matches!(args.opt_level, OptLevel::O0);
+
+        let (builder, unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &filename,
+            &directory,
+            concat!("krillion ", env!("CARGO_PKG_VERSION")),
+            is_optimized,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            context
+                .i32_type()
+                .const_int(debug_metadata_version() as u64, false),
+        );
+
+        // the compile unit is the first file, modules loaded from other files get their own
+        let files = std::iter::once(unit.get_file())
+            .chain(sources.files()[1..].iter().map(|f| {
+                let (filename, directory) = split_path(&f.name);
+                builder.create_file(&filename, &directory)
+            }))
+            .collect();
+
+        Self {
+            builder,
+            unit,
+            scopes: vec![unit.as_debug_info_scope()],
+            target: machine.get_target_data(),
+            sources,
+            files,
+            is_optimized,
+            structs: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn finalize(&self) { self.builder.finalize(); }
+
+    fn line_column(&self, span: &Span) -> (u32, u32) {
+        let pos = byte_to_position(self.sources.get(span.file), span.start);
+        (pos.line as u32 + 1, pos.column as u32 + 1)
+    }
+
+    fn file(&self, span: &Span) -> DIFile<'ctx> { self.files[span.file] }
+
+    fn scope(&self) -> DIScope<'ctx> { *self.scopes.last().unwrap() }
+}
+
+impl<'a, 'ctx> CodeGen<'a, 'ctx> {
+    pub(super) fn debug_location(&self, span: &Span) {
+        if let Some(debug) = &self.debug {
+            let (line, column) = debug.line_column(span);
+            let location = debug.builder.create_debug_location(
+                self.context,
+                line,
+                column,
+                debug.scope(),
+                None,
+            );
+            self.builder.set_current_debug_location(location);
+        }
+    }
+
+    pub(super) fn debug_function_begin(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        name: &str,
+        typ: &Type,
+        span: &Span,
+    ) {
+        let Some(debug) = &self.debug else {
+            return;
+        };
+
+        let (params, ret) = match typ {
+            Type::Function(params, ret, _) => (params, ret),
+            t => unreachable!("{t} is not a function"),
+        };
+
+        let ret = match &ret.0 {
+            Type::BuiltIn(BuiltInType::Unit) => None,
+            r => Some(self.debug_type(r)),
+        };
+        let params = params
+            .iter()
+            .map(|p| self.debug_type(&p.0))
+            .collect::<Vec<DIType>>();
+
+        let file = debug.file(span);
+        let (line, _) = debug.line_column(span);
+        let subroutine = debug
+            .builder
+            .create_subroutine_type(file, ret, &params, DIFlags::ZERO);
+        let subprogram = debug.builder.create_function(
+            debug.unit.as_debug_info_scope(),
+            name,
+            function.get_name().to_str().ok(),
+            file,
+            line,
+            subroutine,
+            false,
+            true,
+            line,
+            DIFlags::PUBLIC,
+            debug.is_optimized,
+        );
+        function.set_subprogram(subprogram);
+
+        self.debug
+            .as_mut()
+            .unwrap()
+            .scopes
+            .push(subprogram.as_debug_info_scope());
+    }
+
+    pub(super) fn debug_scope_begin(&mut self, span: &Span) {
+        if let Some(debug) = &mut self.debug {
+            let (line, column) = debug.line_column(span);
+            let block =
+                debug
+                    .builder
+                    .create_lexical_block(debug.scope(), debug.file(span), line, column);
+            debug.scopes.push(block.as_debug_info_scope());
+        }
+    }
+
+    /// Ends a scope created by either [`Self::debug_function_begin`] or
+    /// [`Self::debug_scope_begin`]
+    pub(super) fn debug_scope_end(&mut self) {
+        if let Some(debug) = &mut self.debug {
+            debug.scopes.pop();
+        }
+    }
+
+    /// Declares a variable living in `ptr`, `arg_no` is the 1-based index for parameters
+    pub(super) fn debug_variable(
+        &self,
+        ptr: PointerValue<'ctx>,
+        name: &str,
+        typ: &Type,
+        span: &Span,
+        arg_no: Option<u32>,
+    ) {
+        let Some(debug) = &self.debug else {
+            return;
+        };
+
+        let file = debug.file(span);
+        let (line, column) = debug.line_column(span);
+        let ditype = self.debug_type(typ);
+
+        let variable = arg_no.map_or_else(
+            || {
+                debug.builder.create_auto_variable(
+                    debug.scope(),
+                    name,
+                    file,
+                    line,
+                    ditype,
+                    true,
+                    DIFlags::ZERO,
+                    0,
+                )
+            },
+            |arg_no| {
+                debug.builder.create_parameter_variable(
+                    debug.scope(),
+                    name,
+                    arg_no,
+                    file,
+                    line,
+                    ditype,
+                    true,
+                    DIFlags::ZERO,
+                )
+            },
+        );
+
+        let location =
+            debug
+                .builder
+                .create_debug_location(self.context, line, column, debug.scope(), None);
+        debug.builder.insert_declare_at_end(
+            ptr,
+            Some(variable),
+            None,
+            location,
+            self.builder.get_insert_block().unwrap(),
+        );
+    }
+
+    /// Describes a global variable of the root, which lives for the whole program
+    pub(super) fn debug_global(
+        &self,
+        global: GlobalValue<'ctx>,
+        name: &str,
+        typ: &Type,
+        span: &Span,
+    ) {
+        let Some(debug) = &self.debug else {
+            return;
+        };
+
+        let (line, _) = debug.line_column(span);
+        let expression = debug.builder.create_global_variable_expression(
+            debug.unit.as_debug_info_scope(),
+            name,
+            global.get_name().to_str().unwrap_or(name),
+            debug.file(span),
+            line,
+            self.debug_type(typ),
+            global.get_linkage() == Linkage::Internal,
+            Some(debug.builder.create_expression(Vec::new())),
+            None,
+            debug.target.get_abi_alignment(&self.basic_type(typ)) * 8,
+        );
+        global.set_metadata(
+            expression.as_metadata_value(self.context),
+            self.context.get_kind_id("dbg"),
+        );
+    }
+
+    fn debug_type(&self, t: &Type) -> DIType<'ctx> {
+        let debug = self.debug.as_ref().unwrap();
+        let size = debug.target.get_bit_size(&self.basic_type(t));
+        let align = debug.target.get_abi_alignment(&self.basic_type(t)) * 8;
+        let name = t.to_string();
+
+        match t {
+            Type::BuiltIn(BuiltInType::Unit) | Type::Unknown(_) | Type::Any => {
+                self.debug_struct(&name, size, align, &[])
+            },
+            Type::BuiltIn(BuiltInType::Str) => {
+                self.debug_slice(&name, &(Type::BuiltIn(BuiltInType::U8), Span::default()))
+            },
+            Type::Slice(t) => self.debug_slice(&name, t),
+            Type::BuiltIn(b) => {
+                use BuiltInType::*;
+                let encoding = match b {
+                    Bool => DW_ATE_BOOLEAN,
+                    F32 | F64 => DW_ATE_FLOAT,
+                    I8 | I16 | I32 | I64 | I128 | Int => DW_ATE_SIGNED,
+                    Char => DW_ATE_UTF,
+                    _ => DW_ATE_UNSIGNED,
+                };
+
+                debug
+                    .builder
+                    .create_basic_type(&name, size, encoding, DIFlags::ZERO)
+                    .unwrap()
+                    .as_type()
+            },
+            Type::Pointer(p) => debug
+                .builder
+                .create_pointer_type(
+                    &name,
+                    self.debug_type(&p.0),
+                    size,
+                    align,
+                    AddressSpace::default(),
+                )
+                .as_type(),
+            Type::Array(e, n) => debug
+                .builder
+                .create_array_type(
+                    self.debug_type(&e.0),
+                    size,
+                    align,
+                    std::slice::from_ref(&(0..n.0 as i64)),
+                )
+                .as_type(),
+            Type::Struct(_, id) if !debug.structs.borrow().contains(id) => {
+                debug.structs.borrow_mut().push(*id);
+
+                let (typ, fields) = &self.structs[id];
+                let members = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (field, t))| {
+                        let ft = self.basic_type(&t.0);
+                        debug
+                            .builder
+                            .create_member_type(
+                                debug.unit.as_debug_info_scope(),
+                                &field.0,
+                                debug.file(&field.1),
+                                debug.line_column(&field.1).0,
+                                debug.target.get_bit_size(&ft),
+                                debug.target.get_abi_alignment(&ft) * 8,
+                                debug.target.offset_of_element(typ, i as u32).unwrap() * 8,
+                                DIFlags::ZERO,
+                                self.debug_type(&t.0),
+                            )
+                            .as_type()
+                    })
+                    .collect::<Vec<DIType>>();
+
+                debug.structs.borrow_mut().pop();
+                self.debug_struct(&name, size, align, &members)
+            },
+            Type::Struct(..) => self.debug_struct(&name, size, align, &[]),
+            Type::Enum(..) => {
+                // only the discriminant is described, the payload depends on it
+                let tag = Type::BuiltIn(BuiltInType::I32);
+                let member = debug
+                    .builder
+                    .create_member_type(
+                        debug.unit.as_debug_info_scope(),
+                        "tag",
+                        debug.unit.get_file(),
+                        0,
+                        32,
+                        32,
+                        0,
+                        DIFlags::ZERO,
+                        self.debug_type(&tag),
+                    )
+                    .as_type();
+
+                self.debug_struct(&name, size, align, &[member])
+            },
+            Type::Function(..) => {
+                // DWARF has no pointer to subroutine type through the C API, use an opaque one
+                let unit = self.debug_type(&Type::BuiltIn(BuiltInType::Unit));
+                debug
+                    .builder
+                    .create_pointer_type(&name, unit, size, align, AddressSpace::default())
+                    .as_type()
+            },
+        }
+    }
+
+    fn debug_slice(&self, name: &str, elem: &AType) -> DIType<'ctx> {
+        let debug = self.debug.as_ref().unwrap();
+        let ptr = Type::Pointer(Box::new(elem.clone()));
+        let len = Type::BuiltIn(BuiltInType::Uint);
+        let slice = self.slice_type();
+
+        let members = [("ptr", &ptr, 0), ("len", &len, 1)].map(|(n, t, i)| {
+            let field = self.basic_type(t);
+            debug
+                .builder
+                .create_member_type(
+                    debug.unit.as_debug_info_scope(),
+                    n,
+                    debug.unit.get_file(),
+                    0,
+                    debug.target.get_bit_size(&field),
+                    debug.target.get_abi_alignment(&field) * 8,
+                    debug.target.offset_of_element(&slice, i).unwrap() * 8,
+                    DIFlags::ZERO,
+                    self.debug_type(t),
+                )
+                .as_type()
+        });
+
+        self.debug_struct(
+            name,
+            debug.target.get_bit_size(&slice),
+            debug.target.get_abi_alignment(&slice) * 8,
+            &members,
+        )
+    }
+
+    fn debug_struct(
+        &self,
+        name: &str,
+        size: u64,
+        align: u32,
+        members: &[DIType<'ctx>],
+    ) -> DIType<'ctx> {
+        let debug = self.debug.as_ref().unwrap();
+        debug
+            .builder
+            .create_struct_type(
+                debug.unit.as_debug_info_scope(),
+                name,
+                debug.unit.get_file(),
+                0,
+                size,
+                align,
+                DIFlags::ZERO,
+                None,
+                members,
+                0,
+                None,
+                name,
+            )
+            .as_type()
+    }
+}
+
+/// The file name and the absolute directory of a path, as debug info wants them
+fn split_path(name: &str) -> (String, String) {
+    let path = Path::new(name);
+    let filename = path
+        .file_name()
+        .map_or_else(|| name.to_string(), |f| f.to_string_lossy().to_string());
+    let directory = std::fs::canonicalize(path)
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_string_lossy().to_string()))
+        .unwrap_or_else(|| ".".to_string());
+
+    (filename, directory)
+}
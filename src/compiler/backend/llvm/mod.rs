@@ -0,0 +1,1677 @@
+use crate::{
+    args::{Args, OptLevel},
+    compiler::{error::*, frontend::*},
+};
+use debug::DebugInfo;
+pub use inkwell::context::Context;
+use inkwell::{
+    AddressSpace, FloatPredicate, GlobalVisibility, IntPredicate, OptimizationLevel,
+    basic_block::BasicBlock,
+    builder::Builder,
+    module::Module,
+    passes::PassBuilderOptions,
+    targets::{
+        CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetData, TargetMachine,
+        TargetTriple,
+    },
+    types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, IntType, StructType},
+    values::{
+        BasicMetadataValueEnum, BasicValue, BasicValueEnum, FloatValue, FunctionValue, GlobalValue,
+        IntValue, PointerValue,
+    },
+};
+use std::{collections::HashMap, path::Path};
+
+mod debug;
+
+pub fn target_machine(args: &Args) -> Result<TargetMachine, String> {
+    let config = InitializationConfig::default();
+
+    // cross compiling uses a generic cpu unless told otherwise, host features only make sense
+    // when compiling for the host
+    let (triple, cpu, features) = match &args.target {
+        Some(triple) => {
+            let triple = TargetTriple::create(triple);
+            initialize_target(&triple, &config);
+
+            (triple, "generic".to_string(), String::new())
+        },
+        None => {
+            Target::initialize_native(&config)?;
+
+            (
+                TargetMachine::get_default_triple(),
+                TargetMachine::get_host_cpu_name().to_string(),
+                TargetMachine::get_host_cpu_features().to_string(),
+            )
+        },
+    };
+
+    let cpu = args.cpu.as_ref().unwrap_or(&cpu);
+    let features = args.target_features.as_ref().unwrap_or(&features);
+    let target = Target::from_triple(&triple)
+        .map_err(|e| format!("unknown target `{triple}`: {}", e.to_string_lossy()))?;
+
+    target
+        .create_target_machine(
+            &triple,
+            cpu,
+            features,
+            codegen_level(args.opt_level),
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .ok_or_else(|| format!("unable to create target machine for `{triple}`"))
+}
+
+/// Initializes the LLVM target of the architecture in `triple`, an unknown triple is reported
+/// when looking up its target
+fn initialize_target(triple: &TargetTriple, config: &InitializationConfig) {
+    let triple = triple.as_str().to_string_lossy();
+
+    match triple.split('-').next().unwrap_or_default() {
+        "x86_64" | "i386" | "i586" | "i686" => Target::initialize_x86(config),
+        "aarch64" | "arm64" => Target::initialize_aarch64(config),
+        arch if arch.starts_with("riscv") => Target::initialize_riscv(config),
+        _ => Target::initialize_all(config),
+    }
+}
+
+pub fn emit_code<'a, 'ctx>(
+    context: &'ctx Context,
+    ast: &NumeratedAst,
+    types: &'a [AType],
+    sources: &'a SourceMap,
+    machine: &TargetMachine,
+    args: &'a Args,
+) -> Module<'ctx> {
+    let module = context.create_module(&args.input);
+    module.set_triple(&machine.get_triple());
+    module.set_data_layout(&machine.get_target_data().get_data_layout());
+
+    let debug = args
+        .debug_info
+        .then(|| DebugInfo::new(context, &module, machine, sources, args));
+
+    let int_type = context.ptr_sized_int_type(&machine.get_target_data(), None);
+
+    let mut codegen = CodeGen {
+        context,
+        module,
+        int_type,
+        target: machine.get_target_data(),
+        builder: context.create_builder(),
+        types,
+        variables: HashMap::new(),
+        functions: HashMap::new(),
+        structs: HashMap::new(),
+        enums: HashMap::new(),
+        variants: HashMap::new(),
+        modules: Vec::new(),
+        loops: Vec::new(),
+        ret: None,
+        args,
+        debug,
+    };
+
+    for n in ast.iter() {
+        codegen.declare_types(n);
+    }
+    codegen.define_types();
+
+    for n in ast.iter() {
+        codegen.declare_functions(n, true);
+    }
+
+    codegen.emit_root(ast);
+
+    if let Some(debug) = &codegen.debug {
+        debug.finalize();
+    }
+
+    if let Err(err) = codegen.module.verify() {
+        panic!("generated invalid module: {}", err.to_string_lossy());
+    }
+
+    codegen.module
+}
+
+pub fn optimize(module: &Module, machine: &TargetMachine, level: OptLevel) -> Result<(), String> {
+    let passes = match level {
+        OptLevel::O0 => "default<O0>",
+        OptLevel::O1 => "default<O1>",
+        OptLevel::O2 => "default<O2>",
+        OptLevel::O3 => "default<O3>",
+        OptLevel::Os => "default<Os>",
+    };
+
+    module
+        .run_passes(passes, machine, PassBuilderOptions::create())
+        .map_err(|e| e.to_string())
+}
+
+const fn codegen_level(level: OptLevel) -> OptimizationLevel {
+    match level {
+        OptLevel::O0 => OptimizationLevel::None,
+        OptLevel::O1 => OptimizationLevel::Less,
+        OptLevel::O2 | OptLevel::Os => OptimizationLevel::Default,
+        OptLevel::O3 => OptimizationLevel::Aggressive,
+    }
+}
+
+/// Runs `main` of the module in memory and returns its exit code, `args` is passed as `argv`
+///
+/// `main` is either generated from the top level statements or declared as
+/// `pub fn main(argc i32, argv &&u8) i32`, only the latter can read the arguments
+pub fn run_jit(module: &Module, level: OptLevel, args: &[&str]) -> Result<i32, String> {
+    let engine = module
+        .create_jit_execution_engine(codegen_level(level))
+        .map_err(|e| e.to_string())?;
+    let main = engine
+        .get_function_value("main")
+        .map_err(|_| "no top level statements or `pub fn main` to run".to_string())?;
+
+    Ok(unsafe { engine.run_function_as_main(main, args) })
+}
+
+pub fn write_object(module: &Module, machine: &TargetMachine, path: &Path) -> Result<(), String> {
+    machine
+        .write_to_file(module, FileType::Object, path)
+        .map_err(|e| e.to_string())
+}
+
+pub fn write_assembly(module: &Module, machine: &TargetMachine, path: &Path) -> Result<(), String> {
+    machine
+        .write_to_file(module, FileType::Assembly, path)
+        .map_err(|e| e.to_string())
+}
+
+pub fn write_ir(module: &Module, path: &Path) -> Result<(), String> {
+    module.print_to_file(path).map_err(|e| e.to_string())
+}
+
+pub fn write_bitcode(module: &Module, path: &Path) -> Result<(), String> {
+    module
+        .write_bitcode_to_path(path)
+        .then_some(())
+        .ok_or_else(|| format!("unable to write bitcode to `{}`", path.display()))
+}
+
+/// The LLVM type, the payloads and the discriminants of an enum
+type EnumLayout<'ctx> = (StructType<'ctx>, Vec<Vec<AType>>, Vec<i128>);
+
+struct CodeGen<'a, 'ctx> {
+    context: &'ctx Context,
+    module: Module<'ctx>,
+    builder: Builder<'ctx>,
+    types: &'a [AType],
+    /// The pointer-sized integer of the target, used for `int` and `uint`
+    int_type: IntType<'ctx>,
+    target: TargetData,
+
+    variables: HashMap<usize, PointerValue<'ctx>>,
+    functions: HashMap<usize, FunctionValue<'ctx>>,
+    /// The LLVM type and the fields of each struct by the id of its declaration
+    structs: HashMap<usize, (StructType<'ctx>, Vec<(AString, AType)>)>,
+    enums: HashMap<usize, EnumLayout<'ctx>>,
+    /// The enum and the index of each variant by its id
+    variants: HashMap<usize, (usize, usize)>,
+    /// Names of the modules the items being declared are in, part of their symbol names
+    modules: Vec<String>,
+    /// Label id and the blocks `continue` and `break` jump to for each loop we're in
+    loops: Vec<(Option<usize>, BasicBlock<'ctx>, BasicBlock<'ctx>)>,
+    /// The return type of the function being emitted, returned values are converted to it
+    ret: Option<&'a Type>,
+
+    args: &'a Args,
+    debug: Option<DebugInfo<'a, 'ctx>>,
+}
+
+impl<'a, 'ctx> CodeGen<'a, 'ctx> {
+    fn declare_types(&mut self, n: &Node<NumeratedNode>) {
+        match &n.kind {
+            NodeKind::StructDeclare { ident, fields, .. } => {
+                let typ = self.context.opaque_struct_type(&ident.0);
+                self.structs.insert(ident.1.1, (typ, fields.clone()));
+            },
+            NodeKind::EnumDeclare {
+                ident, variants, ..
+            } => {
+                let typ = self.context.opaque_struct_type(&ident.0);
+                for (i, v) in variants.iter().enumerate() {
+                    self.variants.insert(v.0.1.1, (ident.1.1, i));
+                }
+                self.enums.insert(
+                    ident.1.1,
+                    (
+                        typ,
+                        variants.iter().map(|v| v.1.clone()).collect(),
+                        discriminants(variants),
+                    ),
+                );
+            },
+            NodeKind::FunctionDeclare {
+                body: Some(body), ..
+            } => self.declare_types(body),
+            NodeKind::Scope { body, .. } => {
+                for n in body.iter() {
+                    self.declare_types(n);
+                }
+            },
+            NodeKind::If { main, els } => {
+                self.declare_types(&main.1);
+                if let Some(els) = els {
+                    self.declare_types(&els.0);
+                }
+            },
+            NodeKind::While { body, .. } | NodeKind::For { body, .. } => self.declare_types(body),
+            NodeKind::Match { arms, .. } => {
+                for arm in arms.iter() {
+                    self.declare_types(&arm.1);
+                }
+            },
+            NodeKind::Module { body, .. } => {
+                for n in body.iter().flatten() {
+                    self.declare_types(n);
+                }
+            },
+            NodeKind::FunctionDeclare { .. }
+            | NodeKind::VarDeclare { .. }
+            | NodeKind::Use(_)
+            | NodeKind::Return(_)
+            | NodeKind::Break(_)
+            | NodeKind::Continue(_)
+            | NodeKind::Expr(_) => {},
+        }
+    }
+
+    /// Sets the bodies of the declared structs and enums, done after all are declared as they can
+    /// refer to each other
+    fn define_types(&self) {
+        for (typ, fields) in self.structs.values() {
+            let fields = fields
+                .iter()
+                .map(|f| self.basic_type(&f.1.0))
+                .collect::<Vec<BasicTypeEnum>>();
+            typ.set_body(&fields, false);
+        }
+
+        // the size of an enum depends on its payloads, which can contain other enums
+        let mut pending = self.enums.keys().copied().collect::<Vec<usize>>();
+        loop {
+            let before = pending.len();
+            pending.retain(|id| !self.define_enum(*id));
+            if pending.is_empty() || pending.len() == before {
+                break;
+            }
+        }
+    }
+
+    /// An enum is its `i32` discriminant followed by enough space for its largest payload, returns
+    /// false if a payload isn't sized yet
+    fn define_enum(&self, id: usize) -> bool {
+        let (typ, payloads, _) = &self.enums[&id];
+        let payloads = payloads
+            .iter()
+            .map(|p| self.payload_type(p))
+            .collect::<Vec<StructType>>();
+
+        if !payloads.iter().all(|p| p.is_sized()) {
+            return false;
+        }
+
+        let size = payloads
+            .iter()
+            .map(|p| self.target.get_abi_size(p))
+            .max()
+            .unwrap_or(0);
+        let align = payloads
+            .iter()
+            .map(|p| self.target.get_abi_alignment(p))
+            .max()
+            .unwrap_or(1);
+
+        let unit = self.context.custom_width_int_type(align * 8);
+        let len = (size + align as u64 - 1) / align as u64;
+        typ.set_body(
+            &[
+                self.context.i32_type().into(),
+                unit.array_type(len as u32).into(),
+            ],
+            false,
+        );
+
+        true
+    }
+
+    fn declare_functions(&mut self, n: &Node<NumeratedNode>, root: bool) {
+        match &n.kind {
+            NodeKind::FunctionDeclare {
+                vis,
+                link,
+                ident,
+                body,
+                ..
+            } => {
+                let typ = self.function_type(self.type_of(ident.1.1));
+                let name = self.symbol_name(&ident.0, vis, link);
+                let function = self.module.add_function(&name, typ, None);
+                // LLVM's default calling convention already is C's, `extern` functions need
+                // nothing more as long as they pass scalars and pointers
+                self.set_linkage(function.as_global_value(), vis, link, root);
+
+                self.functions.insert(ident.1.1, function);
+
+                if let Some(body) = body {
+                    self.declare_functions(body, false);
+                }
+            },
+            NodeKind::Scope { body, .. } => {
+                for n in body.iter() {
+                    self.declare_functions(n, false);
+                }
+            },
+            NodeKind::If { main, els } => {
+                self.declare_functions(&main.1, false);
+                if let Some(els) = els {
+                    self.declare_functions(&els.0, false);
+                }
+            },
+            NodeKind::While { body, .. } | NodeKind::For { body, .. } => {
+                self.declare_functions(body, false)
+            },
+            NodeKind::Match { arms, .. } => {
+                for arm in arms.iter() {
+                    self.declare_functions(&arm.1, false);
+                }
+            },
+            // items of modules are still global
+            NodeKind::Module { ident, body, .. } => {
+                self.modules.push(ident.0.clone());
+                for n in body.iter().flatten() {
+                    self.declare_functions(n, root);
+                }
+                self.modules.pop();
+            },
+            NodeKind::VarDeclare { .. }
+            | NodeKind::Use(_)
+            | NodeKind::StructDeclare { .. }
+            | NodeKind::EnumDeclare { .. }
+            | NodeKind::Return(_)
+            | NodeKind::Break(_)
+            | NodeKind::Continue(_)
+            | NodeKind::Expr(_) => {},
+        }
+    }
+
+    fn emit_root(&mut self, ast: &NumeratedAst) {
+        // top level statements are collected into `main`, root variables become globals so that
+        // functions can refer to them
+        let has_main = ast.iter().any(|n| {
+            !matches!(
+                n.kind,
+                NodeKind::FunctionDeclare { .. }
+                    | NodeKind::StructDeclare { .. }
+                    | NodeKind::EnumDeclare { .. }
+                    | NodeKind::Module { .. }
+                    | NodeKind::Use(_)
+                    | NodeKind::VarDeclare { expr: None, .. }
+            )
+        });
+
+        for n in ast.iter() {
+            if let NodeKind::VarDeclare {
+                vis, link, ident, ..
+            } = &n.kind
+            {
+                let typ = self.basic_type(self.type_of(ident.1.1));
+                let name = self.symbol_name(&ident.0, vis, link);
+                let global = self.module.add_global(typ, None, &name);
+                self.set_linkage(global, vis, link, true);
+                // `extern` variables are only declared, they're defined somewhere else
+                if !matches!(link, Some((Linkage::External, _))) {
+                    global.set_initializer(&const_zero(typ));
+                    self.debug_global(global, &ident.0, self.type_of(ident.1.1), &ident.1.0);
+                }
+                self.variables.insert(ident.1.1, global.as_pointer_value());
+            }
+        }
+
+        if has_main {
+            let main =
+                self.module
+                    .add_function("main", self.context.i32_type().fn_type(&[], false), None);
+            let entry = self.context.append_basic_block(main, "entry");
+            self.builder.position_at_end(entry);
+
+            let main_type = Type::Function(
+                Vec::new(),
+                Box::new((Type::BuiltIn(BuiltInType::I32), Span::default())),
+                false,
+            );
+            self.debug_function_begin(main, "main", &main_type, &Span::default());
+        }
+
+        for n in ast.iter() {
+            match &n.kind {
+                NodeKind::VarDeclare { expr: None, .. } => {},
+                _ => self.emit_node(n),
+            }
+        }
+
+        if has_main && self.is_unterminated() {
+            self.builder
+                .build_return(Some(&self.context.i32_type().const_zero()))
+                .unwrap();
+        }
+    }
+
+    fn emit_function(
+        &mut self,
+        ident: &NString,
+        params: &[(NString, AType, Span)],
+        body: &Node<NumeratedNode>,
+        span: &Span,
+    ) {
+        let function = self.functions[&ident.1.1];
+        let prev = self.builder.get_insert_block();
+        let prev_location = self.builder.get_current_debug_location();
+        let prev_loops = std::mem::take(&mut self.loops);
+        let prev_ret = match self.type_of(ident.1.1) {
+            Type::Function(_, ret, _) => self.ret.replace(&ret.0),
+            t => unreachable!("{t} is not a function"),
+        };
+
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
+
+        self.debug_function_begin(function, &ident.0, self.type_of(ident.1.1), span);
+        self.debug_location(span);
+
+        for (i, p) in params.iter().enumerate() {
+            let value = function.get_nth_param(i as u32).unwrap();
+            let ptr = self.build_alloca(value.get_type(), &p.0.0);
+            self.builder.build_store(ptr, value).unwrap();
+            self.variables.insert(p.0.1.1, ptr);
+
+            self.debug_variable(ptr, &p.0.0, &p.1.0, &p.2, Some(i as u32 + 1));
+        }
+
+        self.emit_node(body);
+
+        if self.is_unterminated() {
+            if function.get_type().get_return_type().is_none() {
+                self.builder.build_return(None).unwrap();
+            } else {
+                self.builder.build_unreachable().unwrap();
+            }
+        }
+
+        self.debug_scope_end();
+        self.loops = prev_loops;
+        self.ret = prev_ret;
+
+        if let Some(prev) = prev {
+            self.builder.position_at_end(prev);
+        }
+
+        match prev_location {
+            Some(location) => self.builder.set_current_debug_location(location),
+            None => self.builder.unset_current_debug_location(),
+        }
+    }
+
+    fn emit_node(&mut self, n: &Node<NumeratedNode>) {
+        if !matches!(n.kind, NodeKind::FunctionDeclare { .. }) {
+            self.debug_location(&n.span);
+        }
+
+        match &n.kind {
+            NodeKind::VarDeclare { ident, expr, .. } => {
+                let typ = self.basic_type(self.type_of(ident.1.1));
+                let ptr = match self.variables.get(&ident.1.1) {
+                    Some(ptr) => *ptr,
+                    None => {
+                        let ptr = self.build_alloca(typ, &ident.0);
+                        self.variables.insert(ident.1.1, ptr);
+                        self.debug_variable(ptr, &ident.0, self.type_of(ident.1.1), &n.span, None);
+                        ptr
+                    },
+                };
+
+                let value = expr.as_ref().map_or_else(
+                    || const_zero(typ),
+                    |expr| self.emit_expr_as(expr, self.type_of(ident.1.1)),
+                );
+                self.builder.build_store(ptr, value).unwrap();
+            },
+            NodeKind::Expr(expr) => {
+                self.emit_expr(expr);
+            },
+            NodeKind::StructDeclare { .. } | NodeKind::EnumDeclare { .. } | NodeKind::Use(_) => {},
+            NodeKind::Module { body, .. } => {
+                for n in body.iter().flatten() {
+                    self.emit_node(n);
+                }
+            },
+            NodeKind::Scope { body, .. } => {
+                self.debug_scope_begin(&n.span);
+                for n in body.iter() {
+                    self.emit_node(n);
+                }
+                self.debug_scope_end();
+            },
+            NodeKind::Return(expr) => {
+                let value = expr.as_ref().map(|expr| match self.ret {
+                    Some(ret) => self.emit_expr_as(expr, ret),
+                    None => self.emit_expr(expr),
+                });
+
+                if self
+                    .current_function()
+                    .get_type()
+                    .get_return_type()
+                    .is_some()
+                {
+                    self.builder
+                        .build_return(value.as_ref().map(|v| v as &dyn BasicValue))
+                        .unwrap();
+                } else {
+                    self.builder.build_return(None).unwrap();
+                }
+
+                self.continue_in_dead_block();
+            },
+            NodeKind::Break(label) => {
+                let (_, _, end_bb) = self.loop_blocks(label);
+                self.builder.build_unconditional_branch(end_bb).unwrap();
+                self.continue_in_dead_block();
+            },
+            NodeKind::Continue(label) => {
+                let (_, continue_bb, _) = self.loop_blocks(label);
+                self.builder
+                    .build_unconditional_branch(continue_bb)
+                    .unwrap();
+                self.continue_in_dead_block();
+            },
+            NodeKind::FunctionDeclare {
+                ident,
+                params,
+                body,
+                ..
+            } => {
+                // functions without a body are only declared
+                if let Some(body) = body {
+                    self.emit_function(ident, params, body, &n.span);
+                }
+            },
+            NodeKind::If { main, els } => {
+                let cond = self.emit_expr(&main.0).into_int_value();
+
+                let function = self.current_function();
+                let then_bb = self.context.append_basic_block(function, "if.then");
+                let else_bb = self.context.append_basic_block(function, "if.else");
+                let end_bb = self.context.append_basic_block(function, "if.end");
+
+                self.builder
+                    .build_conditional_branch(cond, then_bb, else_bb)
+                    .unwrap();
+
+                self.builder.position_at_end(then_bb);
+                self.emit_node(&main.1);
+                self.builder.build_unconditional_branch(end_bb).unwrap();
+
+                self.builder.position_at_end(else_bb);
+                if let Some(els) = els {
+                    self.emit_node(&els.0);
+                }
+                self.builder.build_unconditional_branch(end_bb).unwrap();
+
+                self.builder.position_at_end(end_bb);
+            },
+            NodeKind::While { label, cond, body } => {
+                let function = self.current_function();
+                let cond_bb = self.context.append_basic_block(function, "while.cond");
+                let body_bb = self.context.append_basic_block(function, "while.body");
+                let end_bb = self.context.append_basic_block(function, "while.end");
+
+                self.builder.build_unconditional_branch(cond_bb).unwrap();
+
+                self.builder.position_at_end(cond_bb);
+                let cond = self.emit_expr(cond).into_int_value();
+                self.builder
+                    .build_conditional_branch(cond, body_bb, end_bb)
+                    .unwrap();
+
+                self.builder.position_at_end(body_bb);
+                self.loops
+                    .push((label.as_ref().map(|l| l.1.1), cond_bb, end_bb));
+                self.emit_node(body);
+                self.loops.pop();
+                self.builder.build_unconditional_branch(cond_bb).unwrap();
+
+                self.builder.position_at_end(end_bb);
+            },
+            NodeKind::For {
+                label,
+                ident,
+                range,
+                body,
+            } => {
+                let typ = self.type_of(ident.1.1);
+                let from = self.emit_expr(&range.0);
+                let to = self.emit_expr(&range.1).into_int_value();
+
+                let ptr = self.build_alloca(self.basic_type(typ), &ident.0);
+                self.builder.build_store(ptr, from).unwrap();
+                self.variables.insert(ident.1.1, ptr);
+                self.debug_variable(ptr, &ident.0, typ, &ident.1.0, None);
+
+                let function = self.current_function();
+                let cond_bb = self.context.append_basic_block(function, "for.cond");
+                let body_bb = self.context.append_basic_block(function, "for.body");
+                let step_bb = self.context.append_basic_block(function, "for.step");
+                let end_bb = self.context.append_basic_block(function, "for.end");
+
+                self.builder.build_unconditional_branch(cond_bb).unwrap();
+
+                self.builder.position_at_end(cond_bb);
+                let i = self
+                    .builder
+                    .build_load(self.basic_type(typ), ptr, "")
+                    .unwrap()
+                    .into_int_value();
+                let cond = self
+                    .builder
+                    .build_int_compare(int_predicate(&Operator::LT, is_signed(typ)), i, to, "")
+                    .unwrap();
+                self.builder
+                    .build_conditional_branch(cond, body_bb, end_bb)
+                    .unwrap();
+
+                self.builder.position_at_end(body_bb);
+                self.loops
+                    .push((label.as_ref().map(|l| l.1.1), step_bb, end_bb));
+                self.emit_node(body);
+                self.loops.pop();
+                self.builder.build_unconditional_branch(step_bb).unwrap();
+
+                self.builder.position_at_end(step_bb);
+                let i = self
+                    .builder
+                    .build_load(self.basic_type(typ), ptr, "")
+                    .unwrap()
+                    .into_int_value();
+                let next = self
+                    .builder
+                    .build_int_add(i, i.get_type().const_int(1, false), "")
+                    .unwrap();
+                self.builder.build_store(ptr, next).unwrap();
+                self.builder.build_unconditional_branch(cond_bb).unwrap();
+
+                self.builder.position_at_end(end_bb);
+            },
+            NodeKind::Match { expr, arms } => self.emit_match(expr, arms),
+        }
+    }
+
+    fn emit_match(&mut self, expr: &NExpr, arms: &[Arm<NIdent, NString, Node<NumeratedNode>>]) {
+        let typ = self.type_of(expr.1.1);
+        let ptr = self.emit_lvalue(expr);
+
+        let function = self.current_function();
+        let end_bb = self.context.append_basic_block(function, "match.end");
+
+        // arms after a catch-all or matching an already matched variant are never reached
+        let mut cases = Vec::new();
+        let mut default = None;
+        let mut blocks = Vec::new();
+        for arm in arms.iter() {
+            if let Pattern::Variant { ident, .. } = &arm.0 {
+                let (eid, v) = self.variants[&ident.1];
+                let tag = self.tag(eid, v);
+                if cases.iter().any(|(t, _)| *t == tag) {
+                    continue;
+                }
+
+                let bb = self.context.append_basic_block(function, "match.arm");
+                cases.push((tag, bb));
+                blocks.push((bb, arm));
+            } else {
+                let bb = self.context.append_basic_block(function, "match.arm");
+                default = Some(bb);
+                blocks.push((bb, arm));
+                break;
+            }
+        }
+
+        if let Type::Enum(_, id) = typ {
+            let enum_type = self.enums[id].0;
+            let tag_ptr = self
+                .builder
+                .build_struct_gep(enum_type, ptr, 0, "")
+                .unwrap();
+            let tag = self
+                .builder
+                .build_load(self.context.i32_type(), tag_ptr, "")
+                .unwrap()
+                .into_int_value();
+
+            // the typechecker made sure all variants are covered
+            let default = default.unwrap_or_else(|| {
+                let current = self.builder.get_insert_block().unwrap();
+                let bb = self
+                    .context
+                    .append_basic_block(function, "match.unreachable");
+                self.builder.position_at_end(bb);
+                self.builder.build_unreachable().unwrap();
+                self.builder.position_at_end(current);
+                bb
+            });
+
+            self.builder.build_switch(tag, default, &cases).unwrap();
+        } else {
+            self.builder
+                .build_unconditional_branch(default.unwrap_or(end_bb))
+                .unwrap();
+        }
+
+        for (bb, (pattern, body, span)) in blocks.into_iter() {
+            self.builder.position_at_end(bb);
+
+            match pattern {
+                Pattern::Wildcard => {},
+                Pattern::Binding(b) => {
+                    let value = self
+                        .builder
+                        .build_load(self.basic_type(typ), ptr, "")
+                        .unwrap();
+                    self.bind(b, typ, value, span);
+                },
+                Pattern::Variant { ident, bindings } => {
+                    let (eid, v) = self.variants[&ident.1];
+                    let enum_type = self.enums[&eid].0;
+                    let payload = self.enums[&eid].1[v].clone();
+                    let payload_type = self.payload_type(&payload);
+                    let payload_ptr = self
+                        .builder
+                        .build_struct_gep(enum_type, ptr, 1, "")
+                        .unwrap();
+
+                    for (i, b) in bindings.iter().enumerate() {
+                        if let Some(b) = b {
+                            let field_ptr = self
+                                .builder
+                                .build_struct_gep(payload_type, payload_ptr, i as u32, "")
+                                .unwrap();
+                            let value = self
+                                .builder
+                                .build_load(self.basic_type(&payload[i].0), field_ptr, "")
+                                .unwrap();
+                            self.bind(b, &payload[i].0, value, span);
+                        }
+                    }
+                },
+            }
+
+            self.emit_node(body);
+            if self.is_unterminated() {
+                self.builder.build_unconditional_branch(end_bb).unwrap();
+            }
+        }
+
+        self.builder.position_at_end(end_bb);
+    }
+
+    /// Declares a variable bound by a pattern
+    fn bind(&mut self, ident: &NString, typ: &Type, value: BasicValueEnum<'ctx>, span: &Span) {
+        let ptr = self.build_alloca(value.get_type(), &ident.0);
+        self.builder.build_store(ptr, value).unwrap();
+        self.variables.insert(ident.1.1, ptr);
+        self.debug_variable(ptr, &ident.0, typ, span, None);
+    }
+
+    fn emit_expr(&mut self, expr: &NExpr) -> BasicValueEnum<'ctx> {
+        let typ = self.type_of(expr.1.1);
+
+        match &expr.0 {
+            Expr::Integer(i, _) => self
+                .basic_type(typ)
+                .into_int_type()
+                .const_int_arbitrary_precision(&[*i as u64, (*i >> 64) as u64])
+                .into(),
+            Expr::Float(f) => self
+                .basic_type(typ)
+                .into_float_type()
+                .const_float(*f)
+                .into(),
+            Expr::Bool(b) => self.context.bool_type().const_int(*b as u64, false).into(),
+            Expr::Char(c) => self.context.i32_type().const_int(*c as u64, false).into(),
+            Expr::Str(s) => {
+                let data = self.context.const_string(s.as_bytes(), false);
+                let global = self.module.add_global(data.get_type(), None, ".str");
+                global.set_initializer(&data);
+                global.set_constant(true);
+                global.set_unnamed_addr(true);
+                global.set_linkage(inkwell::module::Linkage::Private);
+
+                self.slice_type()
+                    .const_named_struct(&[
+                        global.as_pointer_value().into(),
+                        self.int_type.const_int(s.len() as u64, false).into(),
+                    ])
+                    .into()
+            },
+            Expr::Ident((_, id)) => match self.functions.get(id) {
+                Some(function) => function.as_global_value().as_pointer_value().into(),
+                None => self
+                    .builder
+                    .build_load(self.basic_type(typ), self.variables[id], "")
+                    .unwrap(),
+            },
+            Expr::UnOp {
+                opr,
+                op: Operator::Ref,
+            } => self.emit_lvalue(opr).into(),
+            Expr::UnOp {
+                opr,
+                op: Operator::Deref,
+            } => {
+                let ptr = self.emit_expr(opr).into_pointer_value();
+                self.builder
+                    .build_load(self.basic_type(typ), ptr, "")
+                    .unwrap()
+            },
+            Expr::UnOp { opr, op } => {
+                let value = self.emit_expr(opr);
+                self.emit_unary(op, value)
+            },
+            Expr::BiOp {
+                lhs,
+                rhs,
+                op: Operator::Assign,
+            } => {
+                let ptr = self.emit_lvalue(lhs);
+                let value = self.emit_expr_as(rhs, self.type_of(lhs.1.1));
+                self.builder.build_store(ptr, value).unwrap();
+
+                self.unit()
+            },
+            Expr::BiOp {
+                lhs,
+                rhs,
+                op: Operator::OpAssign(op),
+            } => {
+                let ltyp = self.type_of(lhs.1.1);
+                let ptr = self.emit_lvalue(lhs);
+                let l = self
+                    .builder
+                    .build_load(self.basic_type(ltyp), ptr, "")
+                    .unwrap();
+                let r = self.emit_expr(rhs);
+
+                let value = self.emit_binary(op, l, r, ltyp);
+                self.builder.build_store(ptr, value).unwrap();
+
+                self.unit()
+            },
+            Expr::BiOp {
+                lhs,
+                rhs,
+                op: Operator::AndAnd,
+            } => self.emit_short_circuit(lhs, rhs, true),
+            Expr::BiOp {
+                lhs,
+                rhs,
+                op: Operator::OrOr,
+            } => self.emit_short_circuit(lhs, rhs, false),
+            Expr::BiOp {
+                op: Operator::Index,
+                ..
+            } => {
+                let ptr = self.emit_lvalue(expr);
+                self.builder
+                    .build_load(self.basic_type(typ), ptr, "")
+                    .unwrap()
+            },
+            Expr::BiOp { lhs, rhs, op } => {
+                let l = self.emit_expr(lhs);
+                let r = self.emit_expr(rhs);
+                self.emit_binary(op, l, r, self.type_of(lhs.1.1))
+            },
+            Expr::FnCall { id, op } => {
+                let params = match self.type_of(id.1.1) {
+                    Type::Function(params, ..) => params,
+                    t => unreachable!("calling {t}"),
+                };
+
+                let call = match &id.0 {
+                    Expr::Ident((_, fid)) if self.functions.contains_key(fid) => {
+                        let args = self.emit_args(op, params);
+                        self.builder.build_call(self.functions[fid], &args, "")
+                    },
+                    _ => {
+                        let fn_type = self.function_type(self.type_of(id.1.1));
+                        let ptr = self.emit_expr(id).into_pointer_value();
+                        let args = self.emit_args(op, params);
+                        self.builder.build_indirect_call(fn_type, ptr, &args, "")
+                    },
+                }
+                .unwrap();
+
+                call.try_as_basic_value()
+                    .left()
+                    .unwrap_or_else(|| self.unit())
+            },
+            Expr::StructLiteral { fields, .. } => {
+                let mut value = self.basic_type(typ).into_struct_type().get_undef();
+                for (field, e) in fields.iter() {
+                    let idx = self.field_index(typ, &field.0);
+                    let v = match typ {
+                        Type::Struct(_, sid) => {
+                            let ftyp = self.structs[sid].1[idx as usize].1.0.clone();
+                            self.emit_expr_as(e, &ftyp)
+                        },
+                        _ => self.emit_expr(e),
+                    };
+                    value = self
+                        .builder
+                        .build_insert_value(value, v, idx, "")
+                        .unwrap()
+                        .into_struct_value();
+                }
+
+                value.into()
+            },
+            Expr::ArrayLiteral(elements) => {
+                let mut value = self.basic_type(typ).into_array_type().get_undef();
+                for (i, e) in elements.iter().enumerate() {
+                    let v = self.emit_expr(e);
+                    value = self
+                        .builder
+                        .build_insert_value(value, v, i as u32, "")
+                        .unwrap()
+                        .into_array_value();
+                }
+
+                value.into()
+            },
+            Expr::RepeatLiteral { value: e, count } => {
+                let array_type = self.basic_type(typ);
+                let value = self.emit_expr(e);
+                if count.0 == 0 {
+                    return const_zero(array_type);
+                }
+
+                // stored in a loop rather than unrolled, the count can be large
+                let ptr = self.build_alloca(array_type, "");
+                let function = self.current_function();
+                let before_bb = self.builder.get_insert_block().unwrap();
+                let body_bb = self.context.append_basic_block(function, "repeat.body");
+                let end_bb = self.context.append_basic_block(function, "repeat.end");
+                self.builder.build_unconditional_branch(body_bb).unwrap();
+
+                self.builder.position_at_end(body_bb);
+                let zero = self.int_type.const_zero();
+                let i = self.builder.build_phi(self.int_type, "").unwrap();
+                i.add_incoming(&[(&zero, before_bb)]);
+                let i_value = i.as_basic_value().into_int_value();
+
+                let elem_ptr = unsafe {
+                    self.builder
+                        .build_in_bounds_gep(array_type, ptr, &[zero, i_value], "")
+                        .unwrap()
+                };
+                self.builder.build_store(elem_ptr, value).unwrap();
+
+                let next = self
+                    .builder
+                    .build_int_add(i_value, self.int_type.const_int(1, false), "")
+                    .unwrap();
+                i.add_incoming(&[(&next, body_bb)]);
+                let cond = self
+                    .builder
+                    .build_int_compare(
+                        IntPredicate::ULT,
+                        next,
+                        self.int_type.const_int(count.0 as u64, false),
+                        "",
+                    )
+                    .unwrap();
+                self.builder
+                    .build_conditional_branch(cond, body_bb, end_bb)
+                    .unwrap();
+
+                self.builder.position_at_end(end_bb);
+                self.builder.build_load(array_type, ptr, "").unwrap()
+            },
+            Expr::Variant { ident, op } => {
+                let (eid, v) = self.variants[&ident.1];
+                let enum_type = self.enums[&eid].0;
+                let ptr = self.build_alloca(enum_type, "");
+
+                let tag_ptr = self
+                    .builder
+                    .build_struct_gep(enum_type, ptr, 0, "")
+                    .unwrap();
+                self.builder.build_store(tag_ptr, self.tag(eid, v)).unwrap();
+
+                if !op.is_empty() {
+                    let mut payload = self.payload_type(&self.enums[&eid].1[v]).get_undef();
+                    for (i, e) in op.iter().enumerate() {
+                        let ptyp = self.enums[&eid].1[v][i].0.clone();
+                        let value = self.emit_expr_as(e, &ptyp);
+                        payload = self
+                            .builder
+                            .build_insert_value(payload, value, i as u32, "")
+                            .unwrap()
+                            .into_struct_value();
+                    }
+
+                    let payload_ptr = self
+                        .builder
+                        .build_struct_gep(enum_type, ptr, 1, "")
+                        .unwrap();
+                    self.builder.build_store(payload_ptr, payload).unwrap();
+                }
+
+                self.builder.build_load(enum_type, ptr, "").unwrap()
+            },
+            Expr::Cast { expr: e, .. } => {
+                let value = self.emit_expr(e);
+                self.emit_cast(value, self.type_of(e.1.1), self.type_of(expr.1.1))
+            },
+            Expr::Field { expr: e, field } => {
+                let s = self.emit_expr(e).into_struct_value();
+                self.builder
+                    .build_extract_value(s, self.field_index(self.type_of(e.1.1), &field.0), "")
+                    .unwrap()
+            },
+        }
+    }
+
+    /// Converts between the types the typechecker allows `as` for
+    fn emit_cast(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        from: &Type,
+        to: &Type,
+    ) -> BasicValueEnum<'ctx> {
+        use BasicTypeEnum as T;
+        use BasicValueEnum as V;
+        let b = &self.builder;
+
+        // enums without payloads convert their discriminant
+        if let Type::Enum(..) = from {
+            let tag = b
+                .build_extract_value(value.into_struct_value(), 0, "")
+                .unwrap();
+            return self.emit_cast(tag, &Type::BuiltIn(BuiltInType::I32), to);
+        }
+
+        match (value, self.basic_type(to)) {
+            (V::IntValue(v), T::IntType(t)) => {
+                let (from_bits, to_bits) = (v.get_type().get_bit_width(), t.get_bit_width());
+                if to_bits < from_bits {
+                    b.build_int_truncate(v, t, "").unwrap().into()
+                } else if to_bits > from_bits && is_signed(from) {
+                    b.build_int_s_extend(v, t, "").unwrap().into()
+                } else if to_bits > from_bits {
+                    b.build_int_z_extend(v, t, "").unwrap().into()
+                } else {
+                    v.into()
+                }
+            },
+            (V::IntValue(v), T::FloatType(t)) if is_signed(from) => {
+                b.build_signed_int_to_float(v, t, "").unwrap().into()
+            },
+            (V::IntValue(v), T::FloatType(t)) => {
+                b.build_unsigned_int_to_float(v, t, "").unwrap().into()
+            },
+            (V::FloatValue(v), T::IntType(t)) if is_signed(to) => {
+                b.build_float_to_signed_int(v, t, "").unwrap().into()
+            },
+            (V::FloatValue(v), T::IntType(t)) => {
+                b.build_float_to_unsigned_int(v, t, "").unwrap().into()
+            },
+            (V::FloatValue(v), T::FloatType(t)) => b.build_float_cast(v, t, "").unwrap().into(),
+            (V::PointerValue(v), T::IntType(t)) => b.build_ptr_to_int(v, t, "").unwrap().into(),
+            (V::IntValue(v), T::PointerType(t)) => b.build_int_to_ptr(v, t, "").unwrap().into(),
+            (V::PointerValue(v), t @ T::PointerType(_)) => b.build_bitcast(v, t, "").unwrap(),
+            // a type cast to itself
+            (value, _) => value,
+        }
+    }
+
+    /// The discriminant of a variant
+    fn tag(&self, enm: usize, variant: usize) -> IntValue<'ctx> {
+        self.context
+            .i32_type()
+            .const_int(self.enums[&enm].2[variant] as i64 as u64, true)
+    }
+
+    fn payload_type(&self, payload: &[AType]) -> StructType<'ctx> {
+        let fields = payload
+            .iter()
+            .map(|t| self.basic_type(&t.0))
+            .collect::<Vec<BasicTypeEnum>>();
+        self.context.struct_type(&fields, false)
+    }
+
+    /// The index of a field in the struct `typ`
+    fn field_index(&self, typ: &Type, field: &str) -> u32 {
+        match typ {
+            Type::Struct(_, id) => self.structs[id]
+                .1
+                .iter()
+                .position(|f| f.0.0 == field)
+                .unwrap() as u32,
+            t => unreachable!("{t} is not a struct"),
+        }
+    }
+
+    /// Arguments after the parameters are passed to a variadic function
+    fn emit_args(&mut self, args: &[NExpr], params: &[AType]) -> Vec<BasicMetadataValueEnum<'ctx>> {
+        args.iter()
+            .enumerate()
+            .map(|(i, a)| match params.get(i) {
+                Some(p) => self.emit_expr_as(a, &p.0).into(),
+                None => {
+                    let value = self.emit_expr(a);
+                    self.promote_variadic(value, self.type_of(a.1.1)).into()
+                },
+            })
+            .collect()
+    }
+
+    /// Emits a value for a place of type `typ`, arrays coerce to slices pointing to them
+    fn emit_expr_as(&mut self, expr: &NExpr, typ: &Type) -> BasicValueEnum<'ctx> {
+        match (typ, self.type_of(expr.1.1)) {
+            (Type::Slice(_), Type::Array(_, len)) => {
+                let ptr = self.emit_lvalue(expr);
+                let len = self.int_type.const_int(len.0 as u64, false);
+
+                let slice = self.slice_type().get_undef();
+                let slice = self.builder.build_insert_value(slice, ptr, 0, "").unwrap();
+                self.builder
+                    .build_insert_value(slice, len, 1, "")
+                    .unwrap()
+                    .into_struct_value()
+                    .into()
+            },
+            _ => self.emit_expr(expr),
+        }
+    }
+
+    /// C's default argument promotions
+    fn promote_variadic(&self, value: BasicValueEnum<'ctx>, typ: &Type) -> BasicValueEnum<'ctx> {
+        let int = self.context.i32_type();
+
+        match value {
+            BasicValueEnum::IntValue(i) if i.get_type().get_bit_width() < int.get_bit_width() => {
+                if is_signed(typ) {
+                    self.builder.build_int_s_extend(i, int, "")
+                } else {
+                    self.builder.build_int_z_extend(i, int, "")
+                }
+                .unwrap()
+                .into()
+            },
+            BasicValueEnum::FloatValue(f) if f.get_type() == self.context.f32_type() => self
+                .builder
+                .build_float_ext(f, self.context.f64_type(), "")
+                .unwrap()
+                .into(),
+            v => v,
+        }
+    }
+
+    fn emit_lvalue(&mut self, expr: &NExpr) -> PointerValue<'ctx> {
+        match &expr.0 {
+            Expr::Ident((_, id)) if self.variables.contains_key(id) => self.variables[id],
+            Expr::UnOp {
+                opr,
+                op: Operator::Deref,
+            } => self.emit_expr(opr).into_pointer_value(),
+            Expr::BiOp {
+                lhs,
+                rhs,
+                op: Operator::Index,
+            } => match self.type_of(lhs.1.1) {
+                Type::Slice(t) => {
+                    let slice = self.emit_expr(lhs).into_struct_value();
+                    let ptr = self
+                        .builder
+                        .build_extract_value(slice, 0, "")
+                        .unwrap()
+                        .into_pointer_value();
+                    let idx = self.emit_expr(rhs).into_int_value();
+
+                    unsafe {
+                        self.builder
+                            .build_in_bounds_gep(self.basic_type(&t.0), ptr, &[idx], "")
+                            .unwrap()
+                    }
+                },
+                arr @ Type::Array(..) => {
+                    let ptr = self.emit_lvalue(lhs);
+                    let idx = self.emit_expr(rhs).into_int_value();
+                    let zero = idx.get_type().const_zero();
+
+                    unsafe {
+                        self.builder
+                            .build_in_bounds_gep(self.basic_type(arr), ptr, &[zero, idx], "")
+                            .unwrap()
+                    }
+                },
+                t => unreachable!("indexing into {t}"),
+            },
+            Expr::Field { expr: e, field } => {
+                let typ = self.type_of(e.1.1);
+                let ptr = self.emit_lvalue(e);
+                let idx = self.field_index(typ, &field.0);
+
+                self.builder
+                    .build_struct_gep(self.basic_type(typ), ptr, idx, "")
+                    .unwrap()
+            },
+            _ => {
+                // not an lvalue, spill it so that a pointer to it can be taken
+                let value = self.emit_expr(expr);
+                let ptr = self.build_alloca(value.get_type(), "");
+                self.builder.build_store(ptr, value).unwrap();
+                ptr
+            },
+        }
+    }
+
+    fn emit_unary(&self, op: &Operator, value: BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx> {
+        match op {
+            Operator::Plus => value,
+            Operator::Minus => match value {
+                BasicValueEnum::FloatValue(f) => {
+                    self.builder.build_float_neg(f, "").unwrap().into()
+                },
+                v => self
+                    .builder
+                    .build_int_neg(v.into_int_value(), "")
+                    .unwrap()
+                    .into(),
+            },
+            Operator::Not => self
+                .builder
+                .build_not(value.into_int_value(), "")
+                .unwrap()
+                .into(),
+            _ => unreachable!("{op:?} is not an unary operator"),
+        }
+    }
+
+    fn emit_binary(
+        &self,
+        op: &Operator,
+        l: BasicValueEnum<'ctx>,
+        r: BasicValueEnum<'ctx>,
+        typ: &Type,
+    ) -> BasicValueEnum<'ctx> {
+        if let (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) = (l, r) {
+            return self.emit_float_binary(op, l, r);
+        }
+
+        let signed = is_signed(typ);
+        let l = self.as_int(l);
+        let r = self.as_int(r);
+
+        let b = &self.builder;
+        match op {
+            Operator::Add => b.build_int_add(l, r, ""),
+            Operator::Sub => b.build_int_sub(l, r, ""),
+            Operator::Mlt => b.build_int_mul(l, r, ""),
+            Operator::Div if signed => b.build_int_signed_div(l, r, ""),
+            Operator::Div => b.build_int_unsigned_div(l, r, ""),
+            Operator::Mod if signed => b.build_int_signed_rem(l, r, ""),
+            Operator::Mod => b.build_int_unsigned_rem(l, r, ""),
+            Operator::And => b.build_and(l, r, ""),
+            Operator::Or => b.build_or(l, r, ""),
+            Operator::Xor => b.build_xor(l, r, ""),
+            Operator::LSh => b.build_left_shift(l, r, ""),
+            Operator::RSh => b.build_right_shift(l, r, signed, ""),
+            Operator::Eq
+            | Operator::NE
+            | Operator::GT
+            | Operator::GE
+            | Operator::LT
+            | Operator::LE => b.build_int_compare(int_predicate(op, signed), l, r, ""),
+            _ => unreachable!("{op:?} is not a binary operator"),
+        }
+        .unwrap()
+        .into()
+    }
+
+    fn emit_float_binary(
+        &self,
+        op: &Operator,
+        l: FloatValue<'ctx>,
+        r: FloatValue<'ctx>,
+    ) -> BasicValueEnum<'ctx> {
+        let b = &self.builder;
+        match op {
+            Operator::Add => b.build_float_add(l, r, "").map(Into::into),
+            Operator::Sub => b.build_float_sub(l, r, "").map(Into::into),
+            Operator::Mlt => b.build_float_mul(l, r, "").map(Into::into),
+            Operator::Div => b.build_float_div(l, r, "").map(Into::into),
+            Operator::Mod => b.build_float_rem(l, r, "").map(Into::into),
+            Operator::Eq
+            | Operator::NE
+            | Operator::GT
+            | Operator::GE
+            | Operator::LT
+            | Operator::LE => b
+                .build_float_compare(float_predicate(op), l, r, "")
+                .map(Into::into),
+            _ => unreachable!("{op:?} is not a float operator"),
+        }
+        .unwrap()
+    }
+
+    fn emit_short_circuit(
+        &mut self,
+        lhs: &NExpr,
+        rhs: &NExpr,
+        is_and: bool,
+    ) -> BasicValueEnum<'ctx> {
+        let function = self.current_function();
+
+        let l = self.emit_expr(lhs).into_int_value();
+        let lhs_bb = self.builder.get_insert_block().unwrap();
+        let rhs_bb = self.context.append_basic_block(function, "sc.rhs");
+        let end_bb = self.context.append_basic_block(function, "sc.end");
+
+        if is_and {
+            self.builder
+                .build_conditional_branch(l, rhs_bb, end_bb)
+                .unwrap();
+        } else {
+            self.builder
+                .build_conditional_branch(l, end_bb, rhs_bb)
+                .unwrap();
+        }
+
+        self.builder.position_at_end(rhs_bb);
+        let r = self.emit_expr(rhs).into_int_value();
+        let rhs_end_bb = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(end_bb).unwrap();
+
+        self.builder.position_at_end(end_bb);
+        let short = self.context.bool_type().const_int(!is_and as u64, false);
+        let phi = self
+            .builder
+            .build_phi(self.context.bool_type(), "")
+            .unwrap();
+        phi.add_incoming(&[(&short, lhs_bb), (&r, rhs_end_bb)]);
+
+        phi.as_basic_value()
+    }
+
+    fn as_int(&self, value: BasicValueEnum<'ctx>) -> IntValue<'ctx> {
+        match value {
+            BasicValueEnum::PointerValue(p) => {
+                self.builder.build_ptr_to_int(p, self.int_type, "").unwrap()
+            },
+            v => v.into_int_value(),
+        }
+    }
+
+    /// `extern` items and `pub` items of the root keep their name so they can be used from C,
+    /// everything else is mangled like `_ZN<len><file><len><module>...<len><name>E` so that items
+    /// of different modules don't collide
+    fn symbol_name(
+        &self,
+        name: &str,
+        vis: &Option<(Visibility, Span)>,
+        link: &Option<(Linkage, Span)>,
+    ) -> String {
+        let exported = matches!(vis, Some((Visibility::Public, _))) && self.modules.is_empty();
+        if exported || matches!(link, Some((Linkage::External, _))) {
+            return name.to_string();
+        }
+
+        let file = Path::new(&self.args.input)
+            .file_stem()
+            .map_or_else(String::new, |s| s.to_string_lossy().to_string());
+
+        let path = std::iter::once(&file)
+            .chain(self.modules.iter())
+            .map(|m| mangle_segment(m))
+            .collect::<String>();
+
+        format!("_ZN{path}{}E", mangle_segment(name))
+    }
+
+    fn set_linkage(
+        &self,
+        global: GlobalValue<'ctx>,
+        vis: &Option<(Visibility, Span)>,
+        link: &Option<(Linkage, Span)>,
+        root: bool,
+    ) {
+        match (link, vis) {
+            _ if !root => global.set_linkage(inkwell::module::Linkage::Internal),
+            (Some((Linkage::Static, _)), _) => {
+                global.set_linkage(inkwell::module::Linkage::Internal)
+            },
+            (Some((Linkage::External, _)), _) => {
+                global.set_linkage(inkwell::module::Linkage::External);
+                global.set_visibility(GlobalVisibility::Default);
+            },
+            (None, Some((Visibility::Public, _))) => {
+                global.set_visibility(GlobalVisibility::Default)
+            },
+            // private items can still be linked together but aren't exported from shared objects
+            (None, None) => global.set_visibility(GlobalVisibility::Hidden),
+        }
+    }
+
+    /// The innermost loop, or the one with the given label
+    fn loop_blocks(
+        &self,
+        label: &Option<NString>,
+    ) -> (Option<usize>, BasicBlock<'ctx>, BasicBlock<'ctx>) {
+        let id = label.as_ref().map(|l| l.1.1);
+        *self
+            .loops
+            .iter()
+            .rev()
+            .find(|l| id.is_none() || l.0 == id)
+            .unwrap()
+    }
+
+    fn build_alloca<T: BasicType<'ctx>>(&self, typ: T, name: &str) -> PointerValue<'ctx> {
+        // allocas are kept in the entry block so loops don't grow the stack
+        let builder = self.context.create_builder();
+        let entry = self.current_function().get_first_basic_block().unwrap();
+
+        match entry.get_first_instruction() {
+            Some(i) => builder.position_before(&i),
+            None => builder.position_at_end(entry),
+        }
+
+        builder.build_alloca(typ, name).unwrap()
+    }
+
+    fn continue_in_dead_block(&self) {
+        let dead = self
+            .context
+            .append_basic_block(self.current_function(), "dead");
+        self.builder.position_at_end(dead);
+    }
+
+    fn is_unterminated(&self) -> bool {
+        self.builder
+            .get_insert_block()
+            .map_or(false, |b| b.get_terminator().is_none())
+    }
+
+    fn current_function(&self) -> FunctionValue<'ctx> {
+        self.builder
+            .get_insert_block()
+            .and_then(|b| b.get_parent())
+            .unwrap()
+    }
+
+    fn type_of(&self, id: usize) -> &'a Type { &self.types[id].0 }
+
+    fn unit(&self) -> BasicValueEnum<'ctx> { self.unit_type().const_zero().into() }
+
+    fn unit_type(&self) -> StructType<'ctx> { self.context.struct_type(&[], false) }
+
+    fn slice_type(&self) -> StructType<'ctx> {
+        self.context
+            .struct_type(&[self.ptr_type(), self.int_type.into()], false)
+    }
+
+    fn ptr_type(&self) -> BasicTypeEnum<'ctx> {
+        self.context
+            .i8_type()
+            .ptr_type(AddressSpace::default())
+            .into()
+    }
+
+    fn basic_type(&self, t: &Type) -> BasicTypeEnum<'ctx> {
+        match t {
+            Type::Pointer(_) | Type::Function(..) => self.ptr_type(),
+            Type::Slice(_) => self.slice_type().into(),
+            Type::Array(t, s) => self.basic_type(&t.0).array_type(s.0 as u32).into(),
+            Type::BuiltIn(b) => self.builtin_type(b),
+            Type::Struct(_, id) => self.structs[id].0.into(),
+            Type::Enum(_, id) => self.enums[id].0.into(),
+            Type::Unknown(_) | Type::Any => self.unit_type().into(),
+        }
+    }
+
+    fn builtin_type(&self, t: &BuiltInType) -> BasicTypeEnum<'ctx> {
+        use BuiltInType::*;
+        match t {
+            Bool => self.context.bool_type().into(),
+            U8 | I8 => self.context.i8_type().into(),
+            U16 | I16 => self.context.i16_type().into(),
+            U32 | I32 | Char => self.context.i32_type().into(),
+            U64 | I64 => self.context.i64_type().into(),
+            Uint | Int => self.int_type.into(),
+            U128 | I128 => self.context.i128_type().into(),
+            F32 => self.context.f32_type().into(),
+            F64 => self.context.f64_type().into(),
+            Str => self.slice_type().into(),
+            Unit => self.unit_type().into(),
+        }
+    }
+
+    fn function_type(&self, t: &Type) -> FunctionType<'ctx> {
+        match t {
+            Type::Function(params, ret, is_variadic) => {
+                let params = params
+                    .iter()
+                    .map(|p| self.basic_type(&p.0).into())
+                    .collect::<Vec<BasicMetadataTypeEnum>>();
+
+                match &ret.0 {
+                    Type::BuiltIn(BuiltInType::Unit) => {
+                        self.context.void_type().fn_type(&params, *is_variadic)
+                    },
+                    r => self.basic_type(r).fn_type(&params, *is_variadic),
+                }
+            },
+            t => unreachable!("{t} is not a function"),
+        }
+    }
+}
+
+fn const_zero(t: BasicTypeEnum<'_>) -> BasicValueEnum<'_> {
+    match t {
+        BasicTypeEnum::ArrayType(t) => t.const_zero().into(),
+        BasicTypeEnum::FloatType(t) => t.const_zero().into(),
+        BasicTypeEnum::IntType(t) => t.const_zero().into(),
+        BasicTypeEnum::PointerType(t) => t.const_zero().into(),
+        BasicTypeEnum::StructType(t) => t.const_zero().into(),
+        BasicTypeEnum::VectorType(t) => t.const_zero().into(),
+    }
+}
+
+/// A segment of a mangled path prefixed by its length. Characters other than ASCII letters, digits
+/// and `_`, as well as a leading digit, are escaped as `$u<hex>$` so that the length counts
+/// characters of the symbol and doesn't run into the segment.
+fn mangle_segment(s: &str) -> String {
+    let escaped = s
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()) {
+                c.to_string()
+            } else {
+                format!("$u{:x}$", c as u32)
+            }
+        })
+        .collect::<String>();
+
+    format!("{}{escaped}", escaped.len())
+}
+
+const fn is_signed(t: &Type) -> bool {
+    use BuiltInType::*;
+    matches!(t, Type::BuiltIn(I8 | I16 | I32 | I64 | I128 | Int))
+}
+
+/// Comparisons are ordered except for `!=`, which is true if either side is NaN
+const fn float_predicate(op: &Operator) -> FloatPredicate {
+    match op {
+        Operator::Eq => FloatPredicate::OEQ,
+        Operator::NE => FloatPredicate::UNE,
+        Operator::GT => FloatPredicate::OGT,
+        Operator::GE => FloatPredicate::OGE,
+        Operator::LT => FloatPredicate::OLT,
+        Operator::LE => FloatPredicate::OLE,
+        _ => unreachable!(),
+    }
+}
+
+const fn int_predicate(op: &Operator, signed: bool) -> IntPredicate {
+    match (op, signed) {
+        (Operator::Eq, _) => IntPredicate::EQ,
+        (Operator::NE, _) => IntPredicate::NE,
+        (Operator::GT, true) => IntPredicate::SGT,
+        (Operator::GT, false) => IntPredicate::UGT,
+        (Operator::GE, true) => IntPredicate::SGE,
+        (Operator::GE, false) => IntPredicate::UGE,
+        (Operator::LT, true) => IntPredicate::SLT,
+        (Operator::LT, false) => IntPredicate::ULT,
+        (Operator::LE, true) => IntPredicate::SLE,
+        (Operator::LE, false) => IntPredicate::ULE,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compiles `src` for the host, [`emit_code`] panics if the module doesn't verify
+    fn compile(src: &str) {
+        let args = Args::default();
+        let machine = target_machine(&args).unwrap();
+        let ptr_bits = machine.get_target_data().get_pointer_byte_size(None) * 8;
+
+        let ((ast, count), _) = numerate_src(src);
+        let (types, errs) = typecheck(&ast, count, ptr_bits);
+        assert!(errs.is_empty(), "{errs:?}");
+
+        let mut sources = SourceMap::default();
+        sources.add(String::new(), src.to_string());
+        let context = Context::create();
+        emit_code(&context, &ast, &types, &sources, &machine, &args);
+    }
+
+    #[test]
+    fn return_array_as_slice() {
+        compile("let g [i32 * 2] = [1, 2]\nfn f() [i32] { return g }");
+    }
+}
@@ -28,6 +28,11 @@ pub enum ParseError {
     UnexpectedDelimiter,
     UnendedScope,
     RanOutTokens,
+    MissingFunctionBody,
+    UnexpectedVariadic,
+    ExpectedItem,
+    ModuleNotFound(String),
+    ModuleCycle(String),
 
     YourMom,
 
@@ -51,6 +56,11 @@ impl CompilerError for ParseError {
             Self::UnendedScope => "scope is not ended".to_string(),
             Self::UnexpectedDelimiter => "unexpected delimiter".to_string(),
             Self::RanOutTokens => "ran out of tokens".to_string(),
+            Self::MissingFunctionBody => "function has no body".to_string(),
+            Self::UnexpectedVariadic => "only `extern` functions can be variadic".to_string(),
+            Self::ExpectedItem => "expected an item in module body".to_string(),
+            Self::ModuleNotFound(path) => format!("cannot find module file `{path}`"),
+            Self::ModuleCycle(path) => format!("module file `{path}` includes itself"),
 
             Self::YourMom => "your mom is waiting you for dinner".to_string(),
 
@@ -66,6 +76,19 @@ impl CompilerError for ParseError {
             Self::UnendedBracket => Some("add a ending bracket".to_string()),
             Self::UnendedScope => Some("add a delimiter `}`".to_string()),
             Self::UnexpectedDelimiter => Some("remove this delimiter".to_string()),
+            Self::UnexpectedVariadic => Some("remove the `...`".to_string()),
+            Self::ExpectedItem => Some(
+                "modules can only contain functions, structs, enums, modules and `use`".to_string(),
+            ),
+            Self::ModuleNotFound(_) => {
+                Some("create the file, or give the module a body `{ ... }`".to_string())
+            },
+            Self::ModuleCycle(_) => {
+                Some("remove the `mod` declaration that leads back to this file".to_string())
+            },
+            Self::MissingFunctionBody => Some(
+                "add a body, or declare it `extern` if it is defined somewhere else".to_string(),
+            ),
             Self::YourMom => Some("have dinner".to_string()),
             _ => None,
         }
@@ -80,28 +103,102 @@ impl CompilerError for ParseError {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct LexerError;
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LexerError {
+    #[default]
+    UnknownToken,
+    UnterminatedString,
+    InvalidEscape,
+    InvalidChar,
+    IntegerOverflow,
+    MissingDigits,
+    ExponentOverflow,
+    MissingExponent,
+}
 
 impl CompilerError for LexerError {
-    fn message(&self) -> String { "lexer error".to_string() }
-    fn consider(&self) -> Option<String> { None }
+    fn message(&self) -> String {
+        match self {
+            Self::UnknownToken => "lexer error".to_string(),
+            Self::UnterminatedString => "string literal is not terminated".to_string(),
+            Self::InvalidEscape => "invalid escape sequence".to_string(),
+            Self::IntegerOverflow => "integer literal is too large".to_string(),
+            Self::MissingDigits => "integer literal has no digits".to_string(),
+            Self::ExponentOverflow => "exponent of float literal is too large".to_string(),
+            Self::MissingExponent => "exponent of float literal has no digits".to_string(),
+            Self::InvalidChar => "character literal must contain exactly one character".to_string(),
+        }
+    }
+
+    fn consider(&self) -> Option<String> {
+        match self {
+            Self::UnknownToken => None,
+            Self::UnterminatedString => Some("add a `\"` at the end".to_string()),
+            Self::InvalidChar => Some("use a string literal `\"...\"` instead".to_string()),
+            Self::IntegerOverflow => {
+                Some("integer literals can't be larger than 128 bits".to_string())
+            },
+            Self::MissingDigits => Some("add digits after the base prefix".to_string()),
+            Self::ExponentOverflow => {
+                Some("exponents of hexadecimal floats have to fit into an `i32`".to_string())
+            },
+            Self::MissingExponent => Some("add digits after the `e` or `p`".to_string()),
+            Self::InvalidEscape => Some(
+                "valid escapes are `\\n`, `\\r`, `\\t`, `\\0`, `\\\\`, `\\\"`, `\\'`, `\\x00` to `\\x7f` and `\\u{...}`"
+                    .to_string(),
+            ),
+        }
+    }
+
     fn severeness(&self) -> Severeness { Severeness::Error }
 }
 
 #[derive(Debug, Clone)]
 pub enum NumerateError {
     NameUndefined,
+    OutsideOfLoop(&'static str),
+    UnknownLabel(String),
+    TypeUndefined(String),
+    NotAType(String),
+    /// The name and what it is instead
+    NotAValue(String, &'static str),
+    DuplicateField(String),
+    DuplicateVariant(String),
+    DuplicateDiscriminant(i128),
+    NotAVariant(String),
+    NotAModule(String),
+    Private(String),
 }
 
 impl CompilerError for NumerateError {
     fn message(&self) -> String {
         match self {
             Self::NameUndefined => "cannot find this name in the current scope".to_string(),
+            Self::OutsideOfLoop(s) => format!("`{s}` outside of a loop"),
+            Self::UnknownLabel(l) => format!("cannot find label `'{l}` in the current scope"),
+            Self::TypeUndefined(t) => format!("cannot find type `{t}` in the current scope"),
+            Self::NotAType(t) => format!("`{t}` is not a type"),
+            Self::NotAValue(t, what) => format!("`{t}` is {what}, not a value"),
+            Self::DuplicateField(f) => format!("field `{f}` is already declared"),
+            Self::DuplicateVariant(v) => format!("variant `{v}` is already declared"),
+            Self::DuplicateDiscriminant(d) => format!("discriminant `{d}` is already used"),
+            Self::NotAVariant(v) => format!("`{v}` is not an enum variant"),
+            Self::NotAModule(m) => format!("`{m}` is not a module or an enum"),
+            Self::Private(i) => format!("`{i}` is private"),
         }
     }
 
-    fn consider(&self) -> Option<String> { None }
+    fn consider(&self) -> Option<String> {
+        match self {
+            Self::OutsideOfLoop(_) => {
+                Some("it can only be used inside of a `while` or `for` body".to_string())
+            },
+            Self::Private(_) => {
+                Some("declare it `pub` to use it outside of its module".to_string())
+            },
+            _ => None,
+        }
+    }
 
     fn severeness(&self) -> Severeness { Severeness::Error }
 }
@@ -117,6 +214,45 @@ pub enum TypeCheckError {
     ExpectedLvalue,
     CyclicType,
     UnexpectedReturn,
+    FloatBitwise,
+    UnknownField {
+        field: String,
+        typ: String,
+    },
+    DuplicateField(String),
+    MissingFields {
+        fields: Vec<String>,
+        typ: String,
+    },
+    NonExhaustiveMatch(Vec<String>),
+    BindingCount {
+        variant: String,
+        expected: usize,
+        found: usize,
+    },
+    IntegerOutOfRange {
+        typ: String,
+        min: i128,
+        max: u128,
+    },
+    InvalidCast {
+        from: String,
+        to: String,
+    },
+    InvalidOperand {
+        op: String,
+        typ: String,
+    },
+    ArrayLengthMismatch {
+        expected: u128,
+        found: u128,
+        because: Span,
+    },
+    ArrayTooLarge(u128),
+    RecursiveType(String),
+    UnreachableArm {
+        because: Span,
+    },
 }
 
 impl CompilerError for TypeCheckError {
@@ -127,18 +263,71 @@ impl CompilerError for TypeCheckError {
             Self::ExpectedLvalue => "expected lvalue".to_string(),
             Self::CyclicType => "cyclic type".to_string(),
             Self::UnexpectedReturn => "unexpected return".to_string(),
+            Self::FloatBitwise => "bitwise operators can't be used on floats".to_string(),
+            Self::UnknownField { field, typ } => format!("`{typ}` has no field named `{field}`"),
+            Self::DuplicateField(f) => format!("field `{f}` is specified more than once"),
+            Self::MissingFields { fields, typ } => format!(
+                "missing fields {} in initializer of `{typ}`",
+                fields
+                    .iter()
+                    .map(|f| format!("`{f}`"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::NonExhaustiveMatch(_) => "non-exhaustive match".to_string(),
+            Self::BindingCount {
+                variant,
+                expected,
+                found,
+            } => format!("expected {expected} bindings for `{variant}`, found {found}"),
+            Self::IntegerOutOfRange { typ, .. } => {
+                format!("integer literal is out of range for `{typ}`")
+            },
+            Self::InvalidCast { from, to } => format!("cannot cast `{from}` as `{to}`"),
+            Self::InvalidOperand { op, typ } => format!("cannot apply `{op}` to `{typ}`"),
+            Self::ArrayLengthMismatch { .. } => "mismatched array lengths".to_string(),
+            Self::ArrayTooLarge(len) => format!("array length `{len}` is too large"),
+            Self::RecursiveType(t) => format!("recursive type `{t}` is infinitely sized"),
+            Self::UnreachableArm { .. } => "unreachable match arm".to_string(),
         }
     }
 
     fn consider(&self) -> Option<String> {
-        /* match self {
-            Self::UnresolvedType => Some("specify the type of the variable".to_string()),
+        match self {
+            // Self::UnresolvedType => Some("specify the type of the variable".to_string()),
+            Self::IntegerOutOfRange { typ, min, max } => Some(format!(
+                "`{typ}` can only hold values from `{min}` to `{max}`"
+            )),
+            Self::NonExhaustiveMatch(missing) => Some(format!(
+                "add arms for {}, or a `_` arm",
+                missing
+                    .iter()
+                    .map(|v| format!("`{v}`"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+            Self::BindingCount { .. } => Some("use `_` for fields that aren't needed".to_string()),
+            Self::InvalidCast { .. } => Some(
+                "only numbers, `bool`, `char`, pointers and enums without payloads can be converted with `as`"
+                    .to_string(),
+            ),
+            Self::ArrayTooLarge(_) => {
+                Some(format!("arrays can have at most {} elements", u32::MAX))
+            },
+            Self::RecursiveType(_) => {
+                Some("store it behind a pointer `&` to break the cycle".to_string())
+            },
+            Self::UnreachableArm { .. } => Some("remove this arm".to_string()),
             _ => None,
-        } */
-        None
+        }
     }
 
-    fn severeness(&self) -> Severeness { Severeness::Error }
+    fn severeness(&self) -> Severeness {
+        match self {
+            Self::UnreachableArm { .. } => Severeness::Warning,
+            _ => Severeness::Error,
+        }
+    }
 
     fn markers(&self, span: Span) -> Vec<Marker> {
         match self {
@@ -158,6 +347,34 @@ impl CompilerError for TypeCheckError {
                     style: MarkerStyle::Primary,
                 },
             ],
+            Self::ArrayLengthMismatch {
+                expected,
+                found,
+                because,
+            } => vec![
+                Marker {
+                    message: format!("expected {expected} elements because of this"),
+                    span: because.clone(),
+                    style: MarkerStyle::Secondary,
+                },
+                Marker {
+                    message: format!("but found {found} here"),
+                    span,
+                    style: MarkerStyle::Primary,
+                },
+            ],
+            Self::UnreachableArm { because } => vec![
+                Marker {
+                    message: "already matched by this arm".to_string(),
+                    span: because.clone(),
+                    style: MarkerStyle::Secondary,
+                },
+                Marker {
+                    message: "so this arm is never reached".to_string(),
+                    span,
+                    style: MarkerStyle::Primary,
+                },
+            ],
             _ => default_markers(span),
         }
     }
@@ -9,9 +9,7 @@ use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone)]
 pub struct ErrorContext<'a> {
-    pub filename: &'a str,
-    pub source: &'a str,
-    pub cat: Vec<usize>,
+    pub sources: &'a SourceMap,
     pub args: &'a Args,
 }
 
@@ -38,7 +36,7 @@ pub enum MarkerStyle {
 }
 
 impl Severeness {
-    const fn as_str(&self, args: &Args) -> &'static str {
+    pub const fn as_str(&self, args: &Args) -> &'static str {
         match (self, args.alt_color) {
             (Self::Error, false) => "\x1b[1;31mError",
             (Self::Error, true) => "\x1b[1;97mError",
@@ -51,24 +49,10 @@ impl Severeness {
 
 pub fn report<E: CompilerError>(
     errs: Vec<AError<E>>,
-    filename: &str,
-    src: &str,
+    sources: &SourceMap,
     args: &Args,
 ) -> (String, bool) {
-    let mut cat = Vec::with_capacity(src.matches('\n').count() + 1);
-    let mut j = 0;
-    for el in src.split('\n') {
-        cat.push(j);
-        j += el.len() + 1;
-    }
-    cat.push(j - 1);
-
-    let ctx = ErrorContext {
-        cat,
-        filename,
-        source: src,
-        args,
-    };
+    let ctx = ErrorContext { sources, args };
 
     let mut out = String::new();
     let mut have_error = false;
@@ -89,20 +73,20 @@ fn report_single<E: CompilerError, W: fmt::Write>(
 ) -> fmt::Result {
     let markers = err.markers(span.clone());
 
-    let endest = byte_to_position(ctx, markers.iter().map(|i| i.span.end).max().unwrap()).line;
-    let line_no_len = (endest + 1).ilog10() as usize + 1;
-
-    let start = byte_to_position(ctx, span.start);
-    let end = byte_to_position(ctx, span.end);
-
-    let mut important_lines = Vec::new();
+    // the file of the primary span comes first, followed by the other files markers point into
+    let mut files = vec![span.file];
     for m in markers.iter() {
-        important_lines.push(byte_to_position(ctx, m.span.start).line);
-        important_lines.push(byte_to_position(ctx, m.span.end).line);
+        if !files.contains(&m.span.file) {
+            files.push(m.span.file);
+        }
     }
 
-    // important_lines.sort();
-    dedup(&mut important_lines);
+    let endest = markers
+        .iter()
+        .map(|m| byte_to_position(ctx.sources.get(m.span.file), m.span.end).line)
+        .max()
+        .unwrap();
+    let line_no_len = (endest + 1).ilog10() as usize + 1;
 
     writeln!(
         out,
@@ -112,56 +96,79 @@ fn report_single<E: CompilerError, W: fmt::Write>(
     )?;
 
     let empty = "";
-    writeln!(
-        out,
-        "\x1b[1;34m{empty:<line_no_len$} ┌─\x1b[0;1m In: \x1b[0m{} \x1b[90m({start} to {end})\x1b[0m",
-        ctx.filename,
-    )?;
-    writeln!(out, "\x1b[1;34m{empty:<line_no_len$} │\x1b[0m")?;
-
-    for (i, l) in important_lines.iter().enumerate() {
-        let l_s1 = l + 1;
-        write!(out, "\x1b[1;34m{l_s1:<line_no_len$} │ \x1b[0m")?;
-
-        if !matches!(ctx.args.error_style, ErrorStyle::NoHighlight) {
-            let hl = ctx.source[ctx.cat[*l]..ctx.cat[*l + 1]]
-                .trim_end()
-                .to_string()
-                + "\n";
-            let mut hl = HighlightToken::lexer(&hl);
-            let mut t0 = hl.next();
-
-            while let Some(t) = t0 {
-                let chunk = hl.slice().replace('\t', "    ").replace('\n', "");
-                let t1 = hl.next();
-                write!(out, "{}", highlight(&t, &t1, &chunk))?;
-
-                t0 = t1;
-            }
-
-            writeln!(out, "\x1b[0m")?;
-        } else {
-            writeln!(
-                out,
-                "{}",
-                ctx.source[ctx.cat[*l]..ctx.cat[*l + 1]].trim_end()
-            )?;
-        }
-
+    for file in files.iter() {
+        let source = ctx.sources.get(*file);
+        let markers = markers
+            .iter()
+            .filter(|m| m.span.file == *file)
+            .collect::<Vec<&Marker>>();
+
+        let primary = *file == span.file;
+        let shown = if primary { &span } else { &markers[0].span };
+        let start = byte_to_position(source, shown.start);
+        let end = byte_to_position(source, shown.end);
+
+        let mut important_lines = Vec::new();
         for m in markers.iter() {
-            m.mark_line(ctx, line_no_len, *l, out)?;
+            important_lines.push(byte_to_position(source, m.span.start).line);
+            important_lines.push(byte_to_position(source, m.span.end).line);
         }
 
-        if important_lines.len() > (i + 1) && important_lines[i + 1] != l_s1 {
-            let omitted = important_lines[i + 1] as isize - l_s1 as isize;
-            let omitted = (omitted - omitted.signum() + 1).abs();
+        // important_lines.sort();
+        dedup(&mut important_lines);
+
+        let corner = if primary { "┌─" } else { "├─" };
+        writeln!(
+            out,
+            "\x1b[1;34m{empty:<line_no_len$} {corner}\x1b[0;1m In: \x1b[0m{} \x1b[90m({start} to {end})\x1b[0m",
+            source.name,
+        )?;
+        writeln!(out, "\x1b[1;34m{empty:<line_no_len$} │\x1b[0m")?;
+
+        for (i, l) in important_lines.iter().enumerate() {
+            let l_s1 = l + 1;
+            write!(out, "\x1b[1;34m{l_s1:<line_no_len$} │ \x1b[0m")?;
+
+            if !matches!(ctx.args.error_style, ErrorStyle::NoHighlight) {
+                let hl = source.src[source.lines[*l]..source.lines[*l + 1]]
+                    .trim_end()
+                    .to_string()
+                    + "\n";
+                let mut hl = HighlightToken::lexer(&hl);
+                let mut t0 = hl.next();
+
+                while let Some(t) = t0 {
+                    let chunk = hl.slice().replace('\t', "    ").replace('\n', "");
+                    let t1 = hl.next();
+                    write!(out, "{}", highlight(&t, &t1, &chunk))?;
+
+                    t0 = t1;
+                }
+
+                writeln!(out, "\x1b[0m")?;
+            } else {
+                writeln!(
+                    out,
+                    "{}",
+                    source.src[source.lines[*l]..source.lines[*l + 1]].trim_end()
+                )?;
+            }
 
-            let s = if omitted != 1 { "s" } else { "" };
-            writeln!(out, "\x1b[0;90m({omitted} line{s} omitted)")?;
+            for m in markers.iter() {
+                m.mark_line(source, line_no_len, *l, out)?;
+            }
+
+            if important_lines.len() > (i + 1) && important_lines[i + 1] != l_s1 {
+                let omitted = important_lines[i + 1] as isize - l_s1 as isize;
+                let omitted = (omitted - omitted.signum() + 1).abs();
+
+                let s = if omitted != 1 { "s" } else { "" };
+                writeln!(out, "\x1b[0;90m({omitted} line{s} omitted)")?;
+            }
         }
-    }
 
-    writeln!(out, "\x1b[1;34m{empty:<line_no_len$} │\x1b[0m")?;
+        writeln!(out, "\x1b[1;34m{empty:<line_no_len$} │\x1b[0m")?;
+    }
 
     if let Some(c) = err.consider() {
         writeln!(
@@ -173,9 +180,9 @@ fn report_single<E: CompilerError, W: fmt::Write>(
     writeln!(out)
 }
 
-fn byte_to_position(ctx: &ErrorContext<'_>, pos: usize) -> Position {
-    let line = ctx.source[..pos].matches('\n').count();
-    let rel = &ctx.source[ctx.cat[line]..pos];
+pub fn byte_to_position(source: &SourceFile, pos: usize) -> Position {
+    let line = source.src[..pos].matches('\n').count();
+    let rel = &source.src[source.lines[line]..pos];
 
     Position {
         line,
@@ -183,9 +190,9 @@ fn byte_to_position(ctx: &ErrorContext<'_>, pos: usize) -> Position {
     }
 }
 
-struct Position {
-    line: usize,
-    column: usize,
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
 }
 
 impl fmt::Display for Position {
@@ -197,17 +204,18 @@ impl fmt::Display for Position {
 impl Marker {
     fn mark_line<W: fmt::Write>(
         &self,
-        ctx: &ErrorContext<'_>,
+        source: &SourceFile,
         offset: usize,
         line: usize,
         out: &mut W,
     ) -> fmt::Result {
-        if self.span.start > ctx.cat[line + 1] || ctx.cat[line] > self.span.end {
+        let lines = &source.lines;
+        if self.span.start > lines[line + 1] || lines[line] > self.span.end {
             return Ok(());
         }
 
-        let start = byte_to_position(ctx, self.span.start.max(ctx.cat[line]));
-        let end = byte_to_position(ctx, self.span.end.min(ctx.cat[line + 1].saturating_sub(1)));
+        let start = byte_to_position(source, self.span.start.max(lines[line]));
+        let end = byte_to_position(source, self.span.end.min(lines[line + 1].saturating_sub(1)));
 
         let empty = "";
 
@@ -217,7 +225,7 @@ impl Marker {
         write!(out, "\x1b[1;34m{empty:<offset$} │ \x1b[0;")?;
         self.style.mark(prepending, length, out)?;
 
-        if self.span.end < ctx.cat[line + 1] {
+        if self.span.end < lines[line + 1] {
             writeln!(out, " {}", self.message)
         } else {
             writeln!(out)
@@ -24,10 +24,39 @@ pub type NIdent = Numerated<Ident<AString>>;
 
 pub type Ast<Kind> = Vec<Node<Kind>>;
 pub type UntypedAst = Ast<UntypedNode>;
-pub type UntypedNode = NodeKind<AExpr, AString>;
+pub type UntypedNode = NodeKind<AExpr, AString, AIdent>;
 
 pub type NumeratedAst = Ast<NumeratedNode>;
-pub type NumeratedNode = NodeKind<NExpr, NString>;
+pub type NumeratedNode = NodeKind<NExpr, NString, NIdent>;
 
 // pub type TypedAst = Ast<TypedNode>;
-// pub type TypedNode = NodeKind<(AExpr, Type), NString>;
+// pub type TypedNode = NodeKind<(AExpr, Type), NString, NIdent>;
+
+/// Runs the frontend on `src` up to numeration, asserting that the earlier stages succeed
+#[cfg(test)]
+pub fn numerate_src(src: &str) -> ((NumeratedAst, usize), Vec<AError<NumerateError>>) {
+    fn assert_ok<E: CompilerError + std::fmt::Debug>(errs: &[AError<E>]) {
+        let errs: Vec<_> = errs
+            .iter()
+            .filter(|e| matches!(e.0.severeness(), Severeness::Error))
+            .collect();
+        assert!(errs.is_empty(), "{errs:?}");
+    }
+
+    let mut l = Token::lexer(src);
+    let (mut buf, errs) = to_atoken_buf(&mut l, 0);
+    assert_ok(&errs);
+    let (mut buf, errs) = preprocess(&mut buf);
+    assert_ok(&errs);
+    let (ast, errs) = parse(&mut buf, src, 0);
+    assert_ok(&errs);
+    numerate(ast)
+}
+
+/// Runs the frontend on `src` up to typechecking for a 64-bit target and returns its errors
+#[cfg(test)]
+pub fn typecheck_src(src: &str) -> Vec<AError<TypeCheckError>> {
+    let ((ast, count), errs) = numerate_src(src);
+    assert!(errs.is_empty(), "{errs:?}");
+    typecheck(&ast, count, 64).1
+}
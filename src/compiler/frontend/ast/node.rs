@@ -22,7 +22,11 @@ pub enum Linkage {
 }
 
 #[derive(Debug, Clone)]
-pub enum NodeKind<Expr: std::fmt::Debug + Clone, ShortIdent: std::fmt::Debug + Clone> {
+pub enum NodeKind<
+    Expr: std::fmt::Debug + Clone,
+    ShortIdent: std::fmt::Debug + Clone,
+    Identifier: std::fmt::Debug + Clone,
+> {
     VarDeclare {
         vis: Option<(Visibility, Span)>,
         link: Option<(Linkage, Span)>,
@@ -31,6 +35,8 @@ pub enum NodeKind<Expr: std::fmt::Debug + Clone, ShortIdent: std::fmt::Debug + C
         expr: Option<Expr>,
     },
     Return(Option<Expr>),
+    Break(Option<ShortIdent>),
+    Continue(Option<ShortIdent>),
     Expr(Expr),
     Scope {
         body: Ast<Self>,
@@ -41,23 +47,97 @@ pub enum NodeKind<Expr: std::fmt::Debug + Clone, ShortIdent: std::fmt::Debug + C
         link: Option<(Linkage, Span)>,
         ident: ShortIdent,
         params: Vec<(ShortIdent, AType, Span)>,
+        /// C-variadic, only allowed on `extern` functions
+        is_variadic: bool,
         return_type: AType,
-        body: Box<Node<Self>>,
+        /// `None` for `extern` functions declared without a body
+        body: Option<Box<Node<Self>>>,
         span: Span,
     },
+    StructDeclare {
+        vis: Option<(Visibility, Span)>,
+        ident: ShortIdent,
+        fields: Vec<(AString, AType)>,
+    },
+    EnumDeclare {
+        vis: Option<(Visibility, Span)>,
+        ident: ShortIdent,
+        variants: Vec<Variant<ShortIdent>>,
+    },
+    /// `None` for `mod name` whose body is in another file and hasn't been loaded yet
+    Module {
+        vis: Option<(Visibility, Span)>,
+        ident: ShortIdent,
+        body: Option<Ast<Self>>,
+    },
+    Use(Identifier),
     If {
         main: (Expr, Box<Node<Self>>, Span),
         els: Option<(Box<Node<Self>>, Span)>,
     },
     While {
+        label: Option<ShortIdent>,
         cond: Expr,
         body: Box<Node<Self>>,
     },
+    /// `for ident in start..end`, `end` is exclusive
+    For {
+        label: Option<ShortIdent>,
+        ident: ShortIdent,
+        range: (Expr, Expr),
+        body: Box<Node<Self>>,
+    },
+    Match {
+        expr: Expr,
+        arms: Vec<Arm<Identifier, ShortIdent, Node<Self>>>,
+    },
+}
+
+impl<
+    Expr: std::fmt::Debug + Clone,
+    ShortIdent: std::fmt::Debug + Clone,
+    Identifier: std::fmt::Debug + Clone,
+> NodeKind<Expr, ShortIdent, Identifier>
+{
+    /// Whether it can appear in the body of a module
+    pub const fn is_item(&self) -> bool {
+        matches!(
+            self,
+            Self::FunctionDeclare { .. }
+                | Self::StructDeclare { .. }
+                | Self::EnumDeclare { .. }
+                | Self::Module { .. }
+                | Self::Use(_)
+        )
+    }
+}
+
+/// Name, payload and explicit discriminant of an enum variant
+pub type Variant<ShortIdent> = (ShortIdent, Vec<AType>, Option<Annotated<i128>>);
+/// Pattern, body and span of a match arm
+pub type Arm<Identifier, ShortIdent, Body> = (Pattern<Identifier, ShortIdent>, Box<Body>, Span);
+
+#[derive(Debug, Clone)]
+pub enum Pattern<Identifier: std::fmt::Debug + Clone, ShortIdent: std::fmt::Debug + Clone> {
+    /// `_`
+    Wildcard,
+    /// Binds the whole value to a name
+    Binding(ShortIdent),
+    /// `Enum::Variant(a, _)`, `None` for payload fields bound to `_`
+    Variant {
+        ident: Identifier,
+        bindings: Vec<Option<ShortIdent>>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum Expr<Extra: std::fmt::Debug + Clone, Identifier: std::fmt::Debug + Clone> {
-    Integer(i128),
+    /// The value and the type given by its suffix
+    Integer(u128, Option<BuiltInType>),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Char(char),
     Ident(Identifier),
     BiOp {
         lhs: Box<(Self, Extra)>,
@@ -72,6 +152,33 @@ pub enum Expr<Extra: std::fmt::Debug + Clone, Identifier: std::fmt::Debug + Clon
         id: Box<(Self, Extra)>,
         op: Vec<(Self, Extra)>,
     },
+    /// `Name { field: value, ... }`
+    StructLiteral {
+        ident: Identifier,
+        fields: Vec<(AString, (Self, Extra))>,
+    },
+    Field {
+        expr: Box<(Self, Extra)>,
+        field: AString,
+    },
+    /// `[a, b, c]`
+    ArrayLiteral(Vec<(Self, Extra)>),
+    /// `[value * count]`
+    RepeatLiteral {
+        value: Box<(Self, Extra)>,
+        count: Annotated<u128>,
+    },
+    /// `expr as typ`
+    Cast {
+        expr: Box<(Self, Extra)>,
+        typ: AType,
+    },
+    /// Constructs an enum variant, the numerator turns paths to variants and calls of them into
+    /// this
+    Variant {
+        ident: Identifier,
+        op: Vec<(Self, Extra)>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -79,9 +186,14 @@ pub enum Type {
     Pointer(Box<AType>),
     Slice(Box<AType>),
     Array(Box<AType>, Annotated<u128>),
-    Function(Vec<AType>, Box<AType>),
+    /// Parameters, return type and whether it's C-variadic
+    Function(Vec<AType>, Box<AType>, bool),
 
     BuiltIn(BuiltInType),
+    /// The name and the id of its declaration
+    Struct(String, usize),
+    /// The name and the id of its declaration
+    Enum(String, usize),
     Unknown(String),
 
     Any,
@@ -109,6 +221,19 @@ pub enum BuiltInType {
     Unit,
 }
 
+/// The discriminant of each variant, the ones without an explicit one follow the previous variant
+pub fn discriminants<T>(variants: &[Variant<T>]) -> Vec<i128> {
+    let mut next = 0i128;
+    variants
+        .iter()
+        .map(|v| {
+            let d = v.2.as_ref().map_or(next, |d| d.0);
+            next = d.saturating_add(1);
+            d
+        })
+        .collect()
+}
+
 impl Type {
     pub fn from_str(s: &str) -> Self {
         use BuiltInType::*;
@@ -146,18 +271,18 @@ impl std::fmt::Display for Type {
             Slice(t) => write!(f, "[{}]", t.0),
             Array(t, s) => write!(f, "[{} * {}]", t.0, s.0),
             BuiltIn(b) => write!(f, "{b}"),
-            Unknown(t) => write!(f, "{t}"),
+            Struct(t, _) | Enum(t, _) | Unknown(t) => write!(f, "{t}"),
             Any => write!(f, "_"),
-            Function(args, ret) => {
-                write!(
-                    f,
-                    "(fn({}) {}",
-                    args.iter()
-                        .map(|a| a.0.to_string())
-                        .collect::<Vec<String>>()
-                        .join(","),
-                    ret.0
-                )?;
+            Function(args, ret, is_variadic) => {
+                let mut args = args
+                    .iter()
+                    .map(|a| a.0.to_string())
+                    .collect::<Vec<String>>();
+                if *is_variadic {
+                    args.push("...".to_string());
+                }
+
+                write!(f, "(fn({}) {}", args.join(","), ret.0)?;
 
                 Ok(())
             },
@@ -1,5 +1,5 @@
 use super::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub type Errors = Vec<AError<NumerateError>>;
 
@@ -11,12 +11,39 @@ pub type Numerated<T> = (T, Numeration);
 struct Numerator {
     errs: Errors,
     index: usize,
+    /// Labels of the loops we're in, `break` and `continue` are only allowed if it's not empty
+    loops: Vec<Option<(String, usize)>>,
+    /// Ids of declared structs, which are types rather than values
+    structs: HashSet<usize>,
+    /// Names and ids of the variants of each declared enum
+    enums: HashMap<usize, Vec<(String, usize)>>,
+    variants: HashSet<usize>,
+    /// Names and ids of the members of each declared module
+    modules: HashMap<usize, HashMap<String, usize>>,
+    /// Ids of items declared `pub`
+    public: HashSet<usize>,
+    /// Modules we're in, their private members are reachable
+    module_stack: Vec<usize>,
+    /// Ids of variables living in the stack frame of a function, every variable but the ones
+    /// declared in the root
+    locals: HashSet<usize>,
+    /// How many scopes deep we are, the root is 1
+    depth: usize,
 }
 
 pub fn numerate(ast: UntypedAst) -> ((NumeratedAst, usize), Errors) {
     let mut numerator = Numerator {
         errs: Vec::new(),
         index: 0,
+        loops: Vec::new(),
+        structs: HashSet::new(),
+        enums: HashMap::new(),
+        variants: HashSet::new(),
+        modules: HashMap::new(),
+        public: HashSet::new(),
+        module_stack: Vec::new(),
+        locals: HashSet::new(),
+        depth: 0,
     };
 
     (
@@ -27,7 +54,30 @@ pub fn numerate(ast: UntypedAst) -> ((NumeratedAst, usize), Errors) {
 
 impl Numerator {
     fn numerate(&mut self, ast: UntypedAst, mut idents: HashMap<String, usize>) -> NumeratedAst {
+        self.depth += 1;
         self.resolve_globals(&ast, &mut idents);
+        let ast = self.numerate_items(ast, idents);
+        self.depth -= 1;
+        ast
+    }
+
+    /// Numerates a scope whose globals are already declared
+    fn numerate_items(
+        &mut self,
+        ast: UntypedAst,
+        mut idents: HashMap<String, usize>,
+    ) -> NumeratedAst {
+        // imports are visible in the whole scope
+        for n in ast.iter() {
+            if let NodeKind::Use(path) = &n.kind {
+                match self.resolve_path(path, &idents) {
+                    Ok(id) => {
+                        idents.insert(path.last().unwrap().0.clone(), id);
+                    },
+                    Err(e) => self.errs.push(e),
+                }
+            }
+        }
 
         let mut new = NumeratedAst::with_capacity(ast.len());
 
@@ -57,9 +107,13 @@ impl Numerator {
                 expr,
             } => {
                 let expr = expr.map(|expr| self.numerate_expr(expr, idents));
+                let typ = typ.map(|typ| self.numerate_type(typ, idents));
 
                 let id = self.assign();
                 idents.insert(ident.0.clone(), id);
+                if self.depth > 1 {
+                    self.locals.insert(id);
+                }
 
                 Node {
                     kind: NodeKind::VarDeclare {
@@ -67,7 +121,7 @@ impl Numerator {
                         link,
 
                         ident: (ident.0, (ident.1, id)),
-                        typ, // TODO:
+                        typ,
                         expr,
                     },
                     span: n.span,
@@ -79,24 +133,47 @@ impl Numerator {
                 link,
                 ident,
                 params,
+                is_variadic,
                 return_type,
                 body,
                 span,
             } => {
-                let (kb, ks) = match body.kind {
-                    NodeKind::Scope { body, span } => (body, span),
-                    _ => unreachable!(),
-                };
-
                 let mut new_params = Vec::with_capacity(params.len());
-                let mut inner_idents = idents.clone();
+                // locals of an enclosing function are in its stack frame, out of reach from here
+                let mut inner_idents: HashMap<String, usize> = idents
+                    .iter()
+                    .filter(|i| !self.locals.contains(i.1))
+                    .map(|(k, v)| (k.clone(), *v))
+                    .collect();
                 for p in params.into_iter() {
                     let p_id = self.assign();
-                    new_params.push(((p.0.0.clone(), (p.0.1, p_id)), p.1, p.2));
+                    self.locals.insert(p_id);
+                    let typ = self.numerate_type(p.1, idents);
+                    new_params.push(((p.0.0.clone(), (p.0.1, p_id)), typ, p.2));
                     inner_idents.insert(p.0.0, p_id);
                 }
 
                 let ident_id = *idents.get(&ident.0).unwrap();
+                let return_type = self.numerate_type(return_type, idents);
+
+                // loops outside of the function can't be broken out of
+                let loops = std::mem::take(&mut self.loops);
+                let body = body.map(|body| {
+                    let (kb, ks) = match body.kind {
+                        NodeKind::Scope { body, span } => (body, span),
+                        _ => unreachable!(),
+                    };
+
+                    Box::new(Node {
+                        kind: NodeKind::Scope {
+                            body: self.numerate(kb, inner_idents),
+                            span: ks,
+                        },
+                        span: body.span,
+                        extra: body.extra,
+                    })
+                });
+                self.loops = loops;
 
                 Node {
                     kind: NodeKind::FunctionDeclare {
@@ -104,9 +181,167 @@ impl Numerator {
                         link,
                         ident: (ident.0, (ident.1, ident_id)),
                         params: new_params,
+                        is_variadic,
                         return_type,
                         span,
-                        body: Box::new(Node {
+                        body,
+                    },
+                    span: n.span,
+                    extra: n.extra,
+                }
+            },
+            NodeKind::StructDeclare { vis, ident, fields } => {
+                let ident_id = *idents.get(&ident.0).unwrap();
+
+                let mut new_fields: Vec<(AString, AType)> = Vec::with_capacity(fields.len());
+                for (name, typ) in fields.into_iter() {
+                    if new_fields.iter().any(|f| f.0.0 == name.0) {
+                        self.errs.push((
+                            NumerateError::DuplicateField(name.0.clone()),
+                            name.1.clone(),
+                        ));
+                    }
+
+                    new_fields.push((name, self.numerate_type(typ, idents)));
+                }
+
+                Node {
+                    kind: NodeKind::StructDeclare {
+                        vis,
+                        ident: (ident.0, (ident.1, ident_id)),
+                        fields: new_fields,
+                    },
+                    span: n.span,
+                    extra: n.extra,
+                }
+            },
+            NodeKind::EnumDeclare {
+                vis,
+                ident,
+                variants,
+            } => {
+                let ident_id = *idents.get(&ident.0).unwrap();
+                let ids = self.enums[&ident_id].clone();
+                let discriminants = discriminants(&variants);
+
+                let mut new_variants: Vec<Variant<NString>> = Vec::with_capacity(variants.len());
+                for (i, ((name, payload, d), (_, id))) in variants.into_iter().zip(ids).enumerate()
+                {
+                    if new_variants.iter().any(|v| v.0.0 == name.0) {
+                        self.errs.push((
+                            NumerateError::DuplicateVariant(name.0.clone()),
+                            name.1.clone(),
+                        ));
+                    } else if discriminants[..i].contains(&discriminants[i]) {
+                        self.errs.push((
+                            NumerateError::DuplicateDiscriminant(discriminants[i]),
+                            d.as_ref().map_or_else(|| name.1.clone(), |d| d.1.clone()),
+                        ));
+                    }
+
+                    let payload = payload
+                        .into_iter()
+                        .map(|t| self.numerate_type(t, idents))
+                        .collect();
+                    new_variants.push(((name.0, (name.1, id)), payload, d));
+                }
+
+                Node {
+                    kind: NodeKind::EnumDeclare {
+                        vis,
+                        ident: (ident.0, (ident.1, ident_id)),
+                        variants: new_variants,
+                    },
+                    span: n.span,
+                    extra: n.extra,
+                }
+            },
+            NodeKind::Module { vis, ident, body } => {
+                let ident_id = *idents.get(&ident.0).unwrap();
+
+                // the body sees the enclosing scope as well as its own members
+                let mut inner_idents = idents.clone();
+                inner_idents.extend(self.modules[&ident_id].clone());
+
+                self.module_stack.push(ident_id);
+                let body = body.map(|body| self.numerate_items(body, inner_idents));
+                self.module_stack.pop();
+
+                Node {
+                    kind: NodeKind::Module {
+                        vis,
+                        ident: (ident.0, (ident.1, ident_id)),
+                        body,
+                    },
+                    span: n.span,
+                    extra: n.extra,
+                }
+            },
+            NodeKind::Use(path) => {
+                // errors are reported when the scope's imports are resolved
+                let id = self.resolve_path(&path, idents).unwrap_or(0);
+
+                Node {
+                    kind: NodeKind::Use((path, id)),
+                    span: n.span,
+                    extra: n.extra,
+                }
+            },
+            NodeKind::Match { expr, arms } => {
+                let expr = self.numerate_expr(expr, idents);
+
+                let mut new_arms = Vec::with_capacity(arms.len());
+                for (pattern, body, span) in arms.into_iter() {
+                    let (kb, ks) = match body.kind {
+                        NodeKind::Scope { body, span } => (body, span),
+                        _ => unreachable!(),
+                    };
+
+                    // bindings are only visible in the body of their arm
+                    let mut inner_idents = idents.clone();
+                    let pattern = match pattern {
+                        Pattern::Wildcard => Pattern::Wildcard,
+                        Pattern::Binding(b) => {
+                            let id = self.assign();
+                            self.locals.insert(id);
+                            inner_idents.insert(b.0.clone(), id);
+                            Pattern::Binding((b.0, (b.1, id)))
+                        },
+                        Pattern::Variant { ident, bindings } => {
+                            let path_span = Span {
+                                start: ident[0].1.start,
+                                end: ident.last().unwrap().1.end,
+                                file: ident[0].1.file,
+                            };
+                            if let Ok(id) = self.resolve_path(&ident, idents) {
+                                if !self.variants.contains(&id) {
+                                    self.errs.push((
+                                        NumerateError::NotAVariant(path_to_string(&ident)),
+                                        path_span,
+                                    ));
+                                }
+                            }
+
+                            Pattern::Variant {
+                                ident: self.numerate_ident(ident, idents),
+                                bindings: bindings
+                                    .into_iter()
+                                    .map(|b| {
+                                        b.map(|b| {
+                                            let id = self.assign();
+                                            self.locals.insert(id);
+                                            inner_idents.insert(b.0.clone(), id);
+                                            (b.0, (b.1, id))
+                                        })
+                                    })
+                                    .collect(),
+                            }
+                        },
+                    };
+
+                    new_arms.push((
+                        pattern,
+                        Box::new(Node {
                             kind: NodeKind::Scope {
                                 body: self.numerate(kb, inner_idents),
                                 span: ks,
@@ -114,6 +349,14 @@ impl Numerator {
                             span: body.span,
                             extra: body.extra,
                         }),
+                        span,
+                    ));
+                }
+
+                Node {
+                    kind: NodeKind::Match {
+                        expr,
+                        arms: new_arms,
                     },
                     span: n.span,
                     extra: n.extra,
@@ -124,6 +367,16 @@ impl Numerator {
                 span: n.span,
                 extra: n.extra,
             },
+            NodeKind::Break(label) => Node {
+                kind: NodeKind::Break(self.resolve_label(label, &n.span, "break")),
+                span: n.span,
+                extra: n.extra,
+            },
+            NodeKind::Continue(label) => Node {
+                kind: NodeKind::Continue(self.resolve_label(label, &n.span, "continue")),
+                span: n.span,
+                extra: n.extra,
+            },
             NodeKind::Scope { body, span } => Node {
                 kind: NodeKind::Scope {
                     body: self.numerate(body, idents.clone()),
@@ -132,20 +385,65 @@ impl Numerator {
                 span: n.span,
                 extra: n.extra,
             },
-            NodeKind::While { cond, body } => {
+            NodeKind::While { label, cond, body } => {
                 let (kb, ks) = match body.kind {
                     NodeKind::Scope { body, span } => (body, span),
                     _ => unreachable!(),
                 };
 
+                let cond = self.numerate_expr(cond, idents);
+
+                let label = self.declare_label(label);
+                let kb = self.numerate(kb, idents.clone());
+                self.loops.pop();
+
                 Node {
                     kind: NodeKind::While {
-                        cond: self.numerate_expr(cond, idents),
+                        label,
+                        cond,
                         body: Box::new(Node {
-                            kind: NodeKind::Scope {
-                                body: self.numerate(kb, idents.clone()),
-                                span: ks,
-                            },
+                            kind: NodeKind::Scope { body: kb, span: ks },
+                            span: body.span,
+                            extra: body.extra,
+                        }),
+                    },
+                    span: n.span,
+                    extra: n.extra,
+                }
+            },
+            NodeKind::For {
+                label,
+                ident,
+                range,
+                body,
+            } => {
+                let (kb, ks) = match body.kind {
+                    NodeKind::Scope { body, span } => (body, span),
+                    _ => unreachable!(),
+                };
+
+                // the range is evaluated outside of the loop variable's scope
+                let range = (
+                    self.numerate_expr(range.0, idents),
+                    self.numerate_expr(range.1, idents),
+                );
+
+                let id = self.assign();
+                self.locals.insert(id);
+                let mut inner_idents = idents.clone();
+                inner_idents.insert(ident.0.clone(), id);
+
+                let label = self.declare_label(label);
+                let kb = self.numerate(kb, inner_idents);
+                self.loops.pop();
+
+                Node {
+                    kind: NodeKind::For {
+                        label,
+                        ident: (ident.0, (ident.1, id)),
+                        range,
+                        body: Box::new(Node {
+                            kind: NodeKind::Scope { body: kb, span: ks },
                             span: body.span,
                             extra: body.extra,
                         }),
@@ -198,19 +496,177 @@ impl Numerator {
 
     fn resolve_globals(&mut self, ast: &UntypedAst, idents: &mut HashMap<String, usize>) {
         for n in ast.iter() {
-            if let NodeKind::FunctionDeclare { ident, .. } = &n.kind {
-                idents.insert(ident.0.clone(), self.assign());
+            match &n.kind {
+                NodeKind::FunctionDeclare { vis, ident, .. } => {
+                    let id = self.assign();
+                    self.declare_visibility(id, vis);
+                    idents.insert(ident.0.clone(), id);
+                },
+                NodeKind::StructDeclare { vis, ident, .. } => {
+                    let id = self.assign();
+                    self.declare_visibility(id, vis);
+                    self.structs.insert(id);
+                    idents.insert(ident.0.clone(), id);
+                },
+                // members are declared right away so paths into modules declared later resolve
+                NodeKind::Module { vis, ident, body } => {
+                    let id = self.assign();
+                    self.declare_visibility(id, vis);
+
+                    let mut members = HashMap::new();
+                    if let Some(body) = body {
+                        self.resolve_globals(body, &mut members);
+                    }
+                    self.modules.insert(id, members);
+                    idents.insert(ident.0.clone(), id);
+                },
+                NodeKind::EnumDeclare {
+                    vis,
+                    ident,
+                    variants,
+                } => {
+                    let id = self.assign();
+                    self.declare_visibility(id, vis);
+                    let variants = variants
+                        .iter()
+                        .map(|v| (v.0.0.clone(), self.assign()))
+                        .collect::<Vec<(String, usize)>>();
+                    self.variants.extend(variants.iter().map(|v| v.1));
+                    self.enums.insert(id, variants);
+                    idents.insert(ident.0.clone(), id);
+                },
+                _ => {},
             }
         }
     }
 
+    fn declare_visibility(&mut self, id: usize, vis: &Option<(Visibility, Span)>) {
+        if let Some((Visibility::Public, _)) = vis {
+            self.public.insert(id);
+        }
+    }
+
+    /// Resolves the names of structs used in a type
+    fn numerate_type(&mut self, typ: AType, idents: &HashMap<String, usize>) -> AType {
+        let t = match typ.0 {
+            Type::Pointer(t) => Type::Pointer(Box::new(self.numerate_type(*t, idents))),
+            Type::Slice(t) => Type::Slice(Box::new(self.numerate_type(*t, idents))),
+            Type::Array(t, s) => Type::Array(Box::new(self.numerate_type(*t, idents)), s),
+            Type::Function(a, r, v) => Type::Function(
+                a.into_iter()
+                    .map(|a| self.numerate_type(a, idents))
+                    .collect(),
+                Box::new(self.numerate_type(*r, idents)),
+                v,
+            ),
+            Type::Unknown(name) => {
+                let path = name
+                    .split("::")
+                    .map(|s| (s.trim().to_string(), typ.1.clone()))
+                    .collect::<AIdent>();
+
+                match self.resolve_path(&path, idents) {
+                    Ok(id) if self.structs.contains(&id) => Type::Struct(name, id),
+                    Ok(id) if self.enums.contains_key(&id) => Type::Enum(name, id),
+                    Ok(_) => {
+                        self.errs
+                            .push((NumerateError::NotAType(name), typ.1.clone()));
+                        Type::Any
+                    },
+                    Err((NumerateError::NameUndefined, _)) => {
+                        self.errs
+                            .push((NumerateError::TypeUndefined(name), typ.1.clone()));
+                        Type::Any
+                    },
+                    Err(e) => {
+                        self.errs.push(e);
+                        Type::Any
+                    },
+                }
+            },
+            t => t,
+        };
+
+        (t, typ.1)
+    }
+
+    /// Enters a loop, the caller has to pop it from `self.loops` after the body
+    fn declare_label(&mut self, label: Option<AString>) -> Option<NString> {
+        let label = label.map(|label| (label.0, (label.1, self.assign())));
+        self.loops
+            .push(label.as_ref().map(|label| (label.0.clone(), label.1.1)));
+        label
+    }
+
+    fn resolve_label(
+        &mut self,
+        label: Option<AString>,
+        span: &Span,
+        keyword: &'static str,
+    ) -> Option<NString> {
+        if self.loops.is_empty() {
+            self.errs
+                .push((NumerateError::OutsideOfLoop(keyword), span.clone()));
+        }
+
+        label.map(|label| {
+            let id = self
+                .loops
+                .iter()
+                .rev()
+                .flatten()
+                .find(|(name, _)| *name == label.0)
+                .map(|(_, id)| *id);
+
+            let id = id.unwrap_or_else(|| {
+                self.errs.push((
+                    NumerateError::UnknownLabel(label.0.clone()),
+                    label.1.clone(),
+                ));
+                0
+            });
+
+            (label.0, (label.1, id))
+        })
+    }
+
     fn numerate_expr(&mut self, expr: AExpr, idents: &HashMap<String, usize>) -> NExpr {
         match expr.0 {
-            Expr::Integer(int) => (Expr::Integer(int), (expr.1, self.assign())),
-            Expr::Ident(id) => (
-                Expr::Ident(self.numerate_ident(id, &expr.1, idents)),
-                (expr.1, self.assign()),
-            ),
+            Expr::Integer(int, suffix) => (Expr::Integer(int, suffix), (expr.1, self.assign())),
+            Expr::Float(f) => (Expr::Float(f), (expr.1, self.assign())),
+            Expr::Bool(b) => (Expr::Bool(b), (expr.1, self.assign())),
+            Expr::Str(s) => (Expr::Str(s), (expr.1, self.assign())),
+            Expr::Char(c) => (Expr::Char(c), (expr.1, self.assign())),
+            Expr::Ident(id) => {
+                let resolved = self.resolve_path(&id, idents);
+                let id = self.numerate_ident(id, idents);
+                match resolved {
+                    Ok(v) if self.variants.contains(&v) => {
+                        return (
+                            Expr::Variant {
+                                ident: id,
+                                op: Vec::new(),
+                            },
+                            (expr.1, self.assign()),
+                        );
+                    },
+                    Ok(t) if self.structs.contains(&t) || self.enums.contains_key(&t) => {
+                        self.errs.push((
+                            NumerateError::NotAValue(path_to_string(&id.0), "a type"),
+                            expr.1.clone(),
+                        ));
+                    },
+                    Ok(m) if self.modules.contains_key(&m) => {
+                        self.errs.push((
+                            NumerateError::NotAValue(path_to_string(&id.0), "a module"),
+                            expr.1.clone(),
+                        ));
+                    },
+                    _ => {},
+                }
+
+                (Expr::Ident(id), (expr.1, self.assign()))
+            },
             Expr::BiOp { lhs, rhs, op } => (
                 Expr::BiOp {
                     lhs: Box::new(self.numerate_expr(*lhs, idents)),
@@ -226,9 +682,58 @@ impl Numerator {
                 },
                 (expr.1, self.assign()),
             ),
-            Expr::FnCall { id, op } => (
-                Expr::FnCall {
-                    id: Box::new(self.numerate_expr(*id, idents)),
+            Expr::FnCall { id, op } => match *id {
+                (Expr::Ident(path), _)
+                    if self
+                        .resolve_path(&path, idents)
+                        .map_or(false, |v| self.variants.contains(&v)) =>
+                {
+                    (
+                        Expr::Variant {
+                            ident: self.numerate_ident(path, idents),
+                            op: op
+                                .into_iter()
+                                .map(|e| self.numerate_expr(e, idents))
+                                .collect(),
+                        },
+                        (expr.1, self.assign()),
+                    )
+                },
+                id => (
+                    Expr::FnCall {
+                        id: Box::new(self.numerate_expr(id, idents)),
+                        op: op
+                            .into_iter()
+                            .map(|e| self.numerate_expr(e, idents))
+                            .collect(),
+                    },
+                    (expr.1, self.assign()),
+                ),
+            },
+            Expr::StructLiteral { ident, fields } => {
+                let defined = self.resolve_path(&ident, idents).is_ok();
+                let ident = self.numerate_ident(ident, idents);
+                if defined && !self.structs.contains(&ident.1) {
+                    self.errs.push((
+                        NumerateError::NotAType(path_to_string(&ident.0)),
+                        expr.1.clone(),
+                    ));
+                }
+
+                (
+                    Expr::StructLiteral {
+                        ident,
+                        fields: fields
+                            .into_iter()
+                            .map(|(name, e)| (name, self.numerate_expr(e, idents)))
+                            .collect(),
+                    },
+                    (expr.1, self.assign()),
+                )
+            },
+            Expr::Variant { ident, op } => (
+                Expr::Variant {
+                    ident: self.numerate_ident(ident, idents),
                     op: op
                         .into_iter()
                         .map(|e| self.numerate_expr(e, idents))
@@ -236,25 +741,88 @@ impl Numerator {
                 },
                 (expr.1, self.assign()),
             ),
+            Expr::ArrayLiteral(elements) => (
+                Expr::ArrayLiteral(
+                    elements
+                        .into_iter()
+                        .map(|e| self.numerate_expr(e, idents))
+                        .collect(),
+                ),
+                (expr.1, self.assign()),
+            ),
+            Expr::RepeatLiteral { value, count } => (
+                Expr::RepeatLiteral {
+                    value: Box::new(self.numerate_expr(*value, idents)),
+                    count,
+                },
+                (expr.1, self.assign()),
+            ),
+            Expr::Cast { expr: e, typ } => (
+                Expr::Cast {
+                    expr: Box::new(self.numerate_expr(*e, idents)),
+                    typ: self.numerate_type(typ, idents),
+                },
+                (expr.1, self.assign()),
+            ),
+            Expr::Field { expr: e, field } => (
+                Expr::Field {
+                    expr: Box::new(self.numerate_expr(*e, idents)),
+                    field,
+                },
+                (expr.1, self.assign()),
+            ),
         }
     }
 
-    fn numerate_ident(
-        &mut self,
-        ident: AIdent,
-        span: &Span,
+    fn numerate_ident(&mut self, ident: AIdent, idents: &HashMap<String, usize>) -> NIdent {
+        let id = self.resolve_path(&ident, idents).unwrap_or_else(|e| {
+            self.errs.push(e);
+            0
+        });
+
+        (ident, id)
+    }
+
+    /// Finds the id a path refers to, walking through modules and enums
+    fn resolve_path(
+        &self,
+        ident: &AIdent,
         idents: &HashMap<String, usize>,
-    ) -> NIdent {
-        if ident.len() == 1 {
-            let id = *idents.get(&ident[0].0).unwrap_or_else(|| {
-                self.errs.push((NumerateError::NameUndefined, span.clone()));
-                &0
-            });
+    ) -> Result<usize, AError<NumerateError>> {
+        let mut id = *idents
+            .get(&ident[0].0)
+            .ok_or_else(|| (NumerateError::NameUndefined, ident[0].1.clone()))?;
+
+        for (i, seg) in ident.iter().enumerate().skip(1) {
+            id = if let Some(members) = self.modules.get(&id) {
+                let member = *members
+                    .get(&seg.0)
+                    .ok_or_else(|| (NumerateError::NameUndefined, seg.1.clone()))?;
+
+                // private members are only reachable from inside of their module
+                if !self.public.contains(&member) && !self.module_stack.contains(&id) {
+                    return Err((
+                        NumerateError::Private(path_to_string(&ident[..=i])),
+                        seg.1.clone(),
+                    ));
+                }
 
-            (ident, id)
-        } else {
-            todo!()
+                member
+            } else if let Some(variants) = self.enums.get(&id) {
+                variants
+                    .iter()
+                    .find(|v| v.0 == seg.0)
+                    .ok_or_else(|| (NumerateError::NameUndefined, seg.1.clone()))?
+                    .1
+            } else {
+                return Err((
+                    NumerateError::NotAModule(path_to_string(&ident[..i])),
+                    ident[i - 1].1.clone(),
+                ));
+            };
         }
+
+        Ok(id)
     }
 
     fn assign(&mut self) -> usize {
@@ -263,3 +831,54 @@ impl Numerator {
         i
     }
 }
+
+fn path_to_string(ident: &[AString]) -> String {
+    ident
+        .iter()
+        .map(|s| s.0.clone())
+        .collect::<Vec<String>>()
+        .join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(src: &str) -> Vec<NumerateError> {
+        numerate_src(src).1.into_iter().map(|e| e.0).collect()
+    }
+
+    #[test]
+    fn private_items() {
+        let src = "mod m {
+                fn secret() i32 { return 1 }
+                struct Hidden { x i32 }
+                pub fn open() i32 { return secret() }
+                pub mod inner { fn deep() {} }
+            }";
+
+        assert!(errors(&format!("{src} fn f() i32 {{ return m::open() }}")).is_empty());
+        assert!(matches!(
+            &errors(&format!("{src} fn f() i32 {{ return m::secret() }}"))[..],
+            [NumerateError::Private(p)] if p == "m::secret"
+        ));
+        assert!(matches!(
+            &errors(&format!("{src} fn f(h m::Hidden) {{}}"))[..],
+            [NumerateError::Private(p)] if p == "m::Hidden"
+        ));
+        assert!(matches!(
+            &errors(&format!("{src} fn f() {{ m::inner::deep() }}"))[..],
+            [NumerateError::Private(p)] if p == "m::inner::deep"
+        ));
+    }
+
+    #[test]
+    fn modules_and_types_are_not_values() {
+        let errs = errors("mod m {} struct S { x i32 } fn f() { let a = m\n let b = S }");
+        assert!(matches!(
+            &errs[..],
+            [NumerateError::NotAValue(m, "a module"), NumerateError::NotAValue(s, "a type")]
+                if m == "m" && s == "S"
+        ));
+    }
+}
@@ -29,11 +29,46 @@ impl<'a> super::Parser<'a> {
     #[inline(always)]
     pub(super) fn parse_expr(&mut self) -> Option<AExpr> { self.parse_expr_climb(0) }
 
+    /// Parses the condition of `if`, `while` and `for`, which is followed by a scope
+    pub(super) fn parse_cond_expr(&mut self) -> Option<AExpr> {
+        let prev = std::mem::replace(&mut self.no_struct_literal, true);
+        let expr = self.parse_expr();
+        self.no_struct_literal = prev;
+        expr
+    }
+
+    /// Parses an expression inside of brackets, where struct literals are always allowed
+    fn parse_bracketed_expr(&mut self) -> Option<AExpr> {
+        let prev = std::mem::replace(&mut self.no_struct_literal, false);
+        let expr = self.parse_expr();
+        self.no_struct_literal = prev;
+        expr
+    }
+
     fn parse_expr_climb(&mut self, percedence: usize) -> Option<AExpr> {
-        let mut rest = self.parse_single()?;
+        let rest = self.parse_single()?;
+        self.parse_expr_rest(rest, percedence)
+    }
 
+    /// Continues climbing from an already parsed left operand
+    fn parse_expr_rest(&mut self, mut rest: AExpr, percedence: usize) -> Option<AExpr> {
         while let Some((tok, _)) = self.buf.next() {
             match tok {
+                // the right side of `as` is a type rather than an expression
+                Token::Operator(Operator::As) if Operator::As.percedence() >= percedence => {
+                    let typ = self.parse_type()?;
+
+                    let start = rest.1.start;
+                    let end = typ.1.end;
+
+                    rest = (
+                        Expr::Cast {
+                            expr: Box::new(rest),
+                            typ,
+                        },
+                        self.span(start, end),
+                    );
+                },
                 Token::Operator(op) if op.is_binary() && op.percedence() >= percedence => {
                     let op = op.clone();
 
@@ -49,7 +84,7 @@ impl<'a> super::Parser<'a> {
                                 rhs: Box::new(right),
                                 op,
                             },
-                            Span { start, end },
+                            self.span(start, end),
                         );
                     } else {
                         rev_insert(&mut rest, right, op);
@@ -60,7 +95,7 @@ impl<'a> super::Parser<'a> {
                     let start = rest.1.start;
 
                     while !matches!(self.buf.peek(), Some((Token::RoBracketE, _))) {
-                        op.push(self.parse_expr()?);
+                        op.push(self.parse_bracketed_expr()?);
 
                         match self.buf.peek() {
                             Some((Token::Comma, _)) => self.buf.idx += 1,
@@ -86,11 +121,11 @@ impl<'a> super::Parser<'a> {
                             id: Box::new(rest),
                             op,
                         },
-                        Span { start, end },
+                        self.span(start, end),
                     );
                 },
                 Token::SqBracketS => {
-                    let idx = self.parse_expr()?;
+                    let idx = self.parse_bracketed_expr()?;
 
                     let start = rest.1.start;
                     let end =
@@ -102,7 +137,44 @@ impl<'a> super::Parser<'a> {
                             rhs: Box::new(idx),
                             op: Operator::Index,
                         },
-                        Span { start, end },
+                        self.span(start, end),
+                    );
+                },
+                Token::Of => {
+                    let field = match self.buf.next() {
+                        Some((Token::Ident, span)) => {
+                            (self.src[span.start..span.end].to_string(), span.clone())
+                        },
+                        Some((t, span)) => {
+                            self.errs.push((
+                                ParseError::UnexpectedToken {
+                                    expected: Some("field name"),
+                                    found: t.clone(),
+                                },
+                                span.clone(),
+                            ));
+
+                            return None;
+                        },
+                        None => {
+                            self.errs.push((
+                                ParseError::RanOutTokens,
+                                self.last_token().unwrap().1.clone(),
+                            ));
+
+                            return None;
+                        },
+                    };
+
+                    let start = rest.1.start;
+                    let end = field.1.end;
+
+                    rest = (
+                        Expr::Field {
+                            expr: Box::new(rest),
+                            field,
+                        },
+                        self.span(start, end),
                     );
                 },
                 _ => {
@@ -118,11 +190,17 @@ impl<'a> super::Parser<'a> {
     fn parse_single(&mut self) -> Option<AExpr> {
         let token = self.buf.next();
         match token {
-            Some((Token::Integer(v), span)) => Some((Expr::Integer(*v as i128), span.clone())),
+            Some((Token::Integer((v, suffix)), span)) => {
+                Some((Expr::Integer(*v, suffix.clone()), span.clone()))
+            },
+            Some((Token::Float(v), span)) => Some((Expr::Float(*v), span.clone())),
+            Some((Token::Bool(b), span)) => Some((Expr::Bool(*b), span.clone())),
+            Some((Token::Str(s), span)) => Some((Expr::Str(s.clone()), span.clone())),
+            Some((Token::Char(c), span)) => Some((Expr::Char(*c), span.clone())),
             Some((Token::Ident, span)) => {
                 let mut total_span = span.clone();
                 let mut segs = vec![(
-                    self.src.slice(span.clone()).unwrap().to_string(),
+                    self.src.slice(span.range()).unwrap().to_string(),
                     span.clone(),
                 )];
 
@@ -133,7 +211,7 @@ impl<'a> super::Parser<'a> {
                         Some((Token::Ident, span)) => {
                             total_span.end = span.end;
                             segs.push((
-                                self.src.slice(span.clone()).unwrap().to_string(),
+                                self.src.slice(span.range()).unwrap().to_string(),
                                 span.clone(),
                             ))
                         },
@@ -159,11 +237,17 @@ impl<'a> super::Parser<'a> {
                     }
                 }
 
+                if !self.no_struct_literal
+                    && matches!(self.buf.peek(), Some((Token::CuBracketS, _)))
+                {
+                    return self.parse_struct_literal(segs, total_span);
+                }
+
                 Some((Expr::Ident(segs), total_span))
             },
             Some((Token::RoBracketS, Span { start, .. })) => {
                 let start = *start;
-                let inner = self.parse_expr()?;
+                let inner = self.parse_bracketed_expr()?;
                 let end = match self.buf.next() {
                     Some((Token::RoBracketE, span)) => span.end,
                     Some((t, span)) => {
@@ -186,7 +270,14 @@ impl<'a> super::Parser<'a> {
                     },
                 };
 
-                Some((inner.0, Span { start, end }))
+                Some((inner.0, self.span(start, end)))
+            },
+            Some((Token::SqBracketS, Span { start, .. })) => {
+                let start = *start;
+                let prev = std::mem::replace(&mut self.no_struct_literal, false);
+                let array = self.parse_array_literal(start);
+                self.no_struct_literal = prev;
+                array
             },
             Some((Token::Operator(op), span)) => {
                 let span = span.clone();
@@ -208,7 +299,7 @@ impl<'a> super::Parser<'a> {
                                 opr: Box::new(acc),
                                 op,
                             },
-                            Span { start, end },
+                            self.span(start, end),
                         );
                     } else {
                         self.errs.push((
@@ -228,7 +319,7 @@ impl<'a> super::Parser<'a> {
             Some((t, span)) => {
                 self.errs.push((
                     ParseError::UnexpectedToken {
-                        expected: Some("value, unary operator or start of round bracket"),
+                        expected: Some("value, unary operator or start of bracket"),
                         found: t.clone(),
                     },
                     span.clone(),
@@ -246,4 +337,129 @@ impl<'a> super::Parser<'a> {
             },
         }
     }
+
+    fn parse_struct_literal(&mut self, ident: AIdent, span: Span) -> Option<AExpr> {
+        // skip the `{`
+        self.buf.next();
+
+        let mut fields = Vec::new();
+        let end = loop {
+            match self.buf.next() {
+                Some((Token::CuBracketE, s)) => break s.end,
+                Some((Token::Comma | Token::Semicolon(_), _)) => {},
+                Some((Token::Ident, s)) => {
+                    let name = (self.src[s.start..s.end].to_string(), s.clone());
+                    assert_token!(Token::Colon, "colon", self);
+                    fields.push((name, self.parse_bracketed_expr()?));
+                },
+                Some((t, s)) => {
+                    self.errs.push((
+                        ParseError::UnexpectedToken {
+                            expected: Some("field or end of struct literal"),
+                            found: t.clone(),
+                        },
+                        s.clone(),
+                    ));
+
+                    return None;
+                },
+                None => {
+                    self.errs.push((
+                        ParseError::UnendedBracket,
+                        self.last_token().unwrap().1.clone(),
+                    ));
+
+                    return None;
+                },
+            }
+        };
+
+        Some((
+            Expr::StructLiteral { ident, fields },
+            self.span(span.start, end),
+        ))
+    }
+
+    fn parse_array_literal(&mut self, start: usize) -> Option<AExpr> {
+        let mut elements = Vec::new();
+        let end = loop {
+            match self.buf.peek() {
+                Some((Token::SqBracketE, s)) => {
+                    let end = s.end;
+                    self.buf.next();
+                    break end;
+                },
+                Some((Token::Comma | Token::Semicolon(_), _)) => {
+                    self.buf.next();
+                },
+                Some(_) => {
+                    // stop before `*` to tell `[value * count]` apart from a multiplication
+                    let value = self.parse_expr_climb(Operator::Mlt.percedence() + 1)?;
+
+                    if elements.is_empty() {
+                        if let Some(count) = self.parse_repeat_count() {
+                            let end = self.buf.next().unwrap().1.end;
+
+                            return Some((
+                                Expr::RepeatLiteral {
+                                    value: Box::new(value),
+                                    count,
+                                },
+                                self.span(start, end),
+                            ));
+                        }
+                    }
+
+                    elements.push(self.parse_expr_rest(value, 0)?);
+
+                    match self.buf.peek() {
+                        Some((Token::Comma | Token::Semicolon(_) | Token::SqBracketE, _)) => {},
+                        Some((t, s)) => {
+                            self.errs.push((
+                                ParseError::UnexpectedToken {
+                                    expected: Some("comma or end of array literal"),
+                                    found: t.clone(),
+                                },
+                                s.clone(),
+                            ));
+
+                            return None;
+                        },
+                        None => {},
+                    }
+                },
+                None => {
+                    self.errs.push((
+                        ParseError::UnendedBracket,
+                        self.last_token().unwrap().1.clone(),
+                    ));
+
+                    return None;
+                },
+            }
+        };
+
+        Some((Expr::ArrayLiteral(elements), self.span(start, end)))
+    }
+
+    /// Parses `* count` if it's followed by the end of the literal, leaving the `]`
+    fn parse_repeat_count(&mut self) -> Option<Annotated<u128>> {
+        let idx = self.buf.idx;
+        match (
+            self.buf.buf.get(idx),
+            self.buf.buf.get(idx + 1),
+            self.buf.buf.get(idx + 2),
+        ) {
+            (
+                Some((Token::Operator(Operator::Mlt), _)),
+                Some((Token::Integer((count, _)), span)),
+                Some((Token::SqBracketE, _)),
+            ) => {
+                let count = (*count, span.clone());
+                self.buf.idx += 2;
+                Some(count)
+            },
+            _ => None,
+        }
+    }
 }
@@ -9,6 +9,10 @@ struct Parser<'a> {
     pub buf: &'a mut Buffer<AToken>,
     pub src: &'a str,
     pub errs: &'a mut Errors,
+    /// The id of the file being parsed, given to the spans put together here
+    pub file: usize,
+    /// In conditions `Name {` starts the body rather than a struct literal
+    pub no_struct_literal: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -16,6 +20,14 @@ impl<'a> Parser<'a> {
         // self.buf.buf.iter().rev().find(|&i| i.1.start != i.1.end)
         self.buf.buf.last()
     }
+
+    const fn span(&self, start: usize, end: usize) -> Span {
+        Span {
+            start,
+            end,
+            file: self.file,
+        }
+    }
 }
 
 /* #[macro_export]
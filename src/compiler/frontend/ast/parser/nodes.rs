@@ -1,6 +1,7 @@
 use super::*;
 
-pub fn parse(buf: &mut Buffer<AToken>, src: &str) -> (UntypedAst, Errors) {
+/// Parses the tokens of the file with the given id
+pub fn parse(buf: &mut Buffer<AToken>, src: &str, file: usize) -> (UntypedAst, Errors) {
     let mut ast = UntypedAst::new();
     let mut errs = Errors::new();
 
@@ -8,6 +9,8 @@ pub fn parse(buf: &mut Buffer<AToken>, src: &str) -> (UntypedAst, Errors) {
         buf,
         src,
         errs: &mut errs,
+        file,
+        no_struct_literal: false,
     };
 
     parser.parse_more(&mut ast, 0);
@@ -21,6 +24,7 @@ pub fn parse(buf: &mut Buffer<AToken>, src: &str) -> (UntypedAst, Errors) {
         Span {
             start: 0,
             end: src.len(),
+            file,
         },
     ));
 
@@ -76,9 +80,17 @@ impl<'a> Parser<'a> {
             Some((Token::CuBracketS, _)) => Self::parse_scope,
             Some((Token::CuBracketE, _)) => return false,
             Some((Token::While, _)) => Self::parse_while,
+            Some((Token::For, _)) => Self::parse_for,
+            Some((Token::Label, _)) => Self::parse_labeled,
             Some((Token::Fn, _)) => Self::parse_fn,
+            Some((Token::Struct, _)) => Self::parse_struct,
+            Some((Token::Enum, _)) => Self::parse_enum,
+            Some((Token::Mod, _)) => Self::parse_mod,
+            Some((Token::Use, _)) => Self::parse_use,
             Some((Token::Return, _)) => Self::parse_return,
+            Some((Token::Break | Token::Continue, _)) => Self::parse_loop_control,
             Some((Token::If, _)) => Self::parse_if,
+            Some((Token::Match, _)) => Self::parse_match,
             Some(_) => Self::parse_standalone_expr,
             None => return false,
         })(self, ast, visibility, linkage, NodeExtra::default(), depth);
@@ -127,15 +139,14 @@ impl<'a> Parser<'a> {
 
         ast.push(Node {
             kind: NodeKind::Expr(expr),
-            span: Span {
-                start: span.start,
-                end: self
-                    .buf
+            span: self.span(
+                span.start,
+                self.buf
                     .current()
                     .unwrap_or_else(|| self.last_token().unwrap())
                     .1
                     .start,
-            },
+            ),
             extra,
         });
     }
@@ -205,10 +216,7 @@ impl<'a> Parser<'a> {
                 typ,
                 expr,
             },
-            span: Span {
-                start: span.start,
-                end,
-            },
+            span: self.span(span.start, end),
             extra,
         });
     }
@@ -269,10 +277,7 @@ impl<'a> Parser<'a> {
             _ => {
                 self.errs.push((
                     ParseError::UnendedScope,
-                    Span {
-                        start,
-                        end: self.last_token().unwrap().1.end,
-                    },
+                    self.span(start, self.last_token().unwrap().1.end),
                 ));
                 return None;
             },
@@ -282,12 +287,12 @@ impl<'a> Parser<'a> {
             Node {
                 kind: NodeKind::Scope {
                     body: new_ast,
-                    span: Span { start, end },
+                    span: self.span(start, end),
                 },
-                span: Span { start, end },
+                span: self.span(start, end),
                 extra,
             },
-            Span { start, end },
+            self.span(start, end),
         ))
     }
 
@@ -304,7 +309,7 @@ impl<'a> Parser<'a> {
 
         let start = self.buf.next().unwrap().1.clone().start;
 
-        let expr = if let Some(expr) = self.parse_expr() {
+        let expr = if let Some(expr) = self.parse_cond_expr() {
             expr
         } else {
             return;
@@ -318,11 +323,61 @@ impl<'a> Parser<'a> {
         let body = Box::new(body);
 
         ast.push(Node {
-            kind: NodeKind::While { cond: expr, body },
-            span: Span {
-                start,
-                end: span.end,
+            kind: NodeKind::While {
+                label: None,
+                cond: expr,
+                body,
             },
+            span: self.span(start, span.end),
+            extra,
+        });
+    }
+
+    fn parse_for(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        depth: usize,
+    ) {
+        vis!(disable vis, self);
+        link!(disable link, self);
+
+        let start = self.buf.next().unwrap().1.clone().start;
+
+        let ident = unwrap_ident!(self);
+        assert_token!(Token::In, "`in`", self);
+
+        let from = if let Some(expr) = self.parse_expr() {
+            expr
+        } else {
+            return;
+        };
+
+        assert_token!(Token::Range, "range operator", self);
+
+        let to = if let Some(expr) = self.parse_cond_expr() {
+            expr
+        } else {
+            return;
+        };
+
+        let (body, span) = if let Some(a) = self.parse_scope_impl(depth, NodeExtra::default()) {
+            a
+        } else {
+            return;
+        };
+        let body = Box::new(body);
+
+        ast.push(Node {
+            kind: NodeKind::For {
+                label: None,
+                ident,
+                range: (from, to),
+                body,
+            },
+            span: self.span(start, span.end),
             extra,
         });
     }
@@ -348,14 +403,88 @@ impl<'a> Parser<'a> {
         let end = value.as_ref().map_or(span.end, |a| a.1.end);
         ast.push(Node {
             kind: NodeKind::Return(value),
-            span: Span {
-                start: span.start,
-                end,
-            },
+            span: self.span(span.start, end),
             extra,
         });
     }
 
+    fn parse_labeled(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        depth: usize,
+    ) {
+        let span = self.buf.next().unwrap().1.clone();
+        let label = (self.src[span.start + 1..span.end].to_string(), span.clone());
+
+        assert_token!(Token::Colon, "colon", self);
+
+        let len = ast.len();
+        match self.buf.peek() {
+            Some((Token::While, _)) => self.parse_while(ast, vis, link, extra, depth),
+            Some((Token::For, _)) => self.parse_for(ast, vis, link, extra, depth),
+            Some((t, s)) => error!(
+                ParseError::UnexpectedToken {
+                    expected: Some("`while` or `for`"),
+                    found: t.clone(),
+                },
+                s.clone(),
+                self
+            ),
+            None => error!(ParseError::RanOutTokens, span, self),
+        }
+
+        if ast.len() > len {
+            if let Some(Node {
+                kind: NodeKind::While { label: l, .. } | NodeKind::For { label: l, .. },
+                span: s,
+                ..
+            }) = ast.last_mut()
+            {
+                *l = Some(label);
+                s.start = span.start;
+            }
+        }
+    }
+
+    fn parse_loop_control(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        _depth: usize,
+    ) {
+        vis!(disable vis, self);
+        link!(disable link, self);
+
+        let (is_break, mut span) = match self.buf.next().unwrap() {
+            (Token::Break, span) => (true, span.clone()),
+            (Token::Continue, span) => (false, span.clone()),
+            _ => unreachable!(),
+        };
+
+        let label = match self.buf.peek() {
+            Some((Token::Label, s)) => {
+                let s = s.clone();
+                self.buf.next();
+                span.end = s.end;
+                Some((self.src[s.start + 1..s.end].to_string(), s))
+            },
+            _ => None,
+        };
+
+        let kind = if is_break {
+            NodeKind::Break(label)
+        } else {
+            NodeKind::Continue(label)
+        };
+
+        ast.push(Node { kind, span, extra });
+    }
+
     fn parse_if(
         &mut self,
         ast: &mut UntypedAst,
@@ -369,7 +498,7 @@ impl<'a> Parser<'a> {
 
         let start = self.buf.next().unwrap().1.clone().start;
 
-        let expr = if let Some(expr) = self.parse_expr() {
+        let expr = if let Some(expr) = self.parse_cond_expr() {
             expr
         } else {
             return;
@@ -392,7 +521,7 @@ impl<'a> Parser<'a> {
                         if !tmp.is_empty() {
                             let node = tmp.swap_remove(0);
                             let end = node.span.end;
-                            Some((Box::new(node), Span { start, end }))
+                            Some((Box::new(node), self.span(start, end)))
                         } else {
                             None
                         }
@@ -406,13 +535,7 @@ impl<'a> Parser<'a> {
                             };
                         let body = Box::new(body);
 
-                        Some((
-                            body,
-                            Span {
-                                start,
-                                end: span.end,
-                            },
-                        ))
+                        Some((body, self.span(start, span.end)))
                     },
                 }
             },
@@ -424,10 +547,7 @@ impl<'a> Parser<'a> {
                 main: (expr, body, span.clone()),
                 els,
             },
-            span: Span {
-                start,
-                end: span.end,
-            },
+            span: self.span(start, span.end),
             extra,
         });
     }
@@ -449,36 +569,70 @@ impl<'a> Parser<'a> {
         assert_token!(Token::RoBracketS, "start of argument list", self).start;
 
         let mut params = Vec::new();
-        while !matches!(self.buf.peek(), Some((Token::RoBracketE, _))) {
-            let param = self.parse_fn_param();
-            if let Some(param) = param {
-                params.push(param);
-            };
-        }
+        let mut is_variadic = false;
+        loop {
+            match self.buf.peek() {
+                Some((Token::RoBracketE, _)) => {
+                    self.buf.next();
+                    break;
+                },
+                Some((Token::Ellipsis, s)) => {
+                    let s = s.clone();
+                    self.buf.next();
 
-        self.buf.next();
+                    if !matches!(link, Some((Linkage::External, _))) {
+                        self.errs.push((ParseError::UnexpectedVariadic, s));
+                    }
+
+                    is_variadic = true;
+                    assert_token!(Token::RoBracketE, "end of argument list", self);
+                    break;
+                },
+                Some(_) => {
+                    if let Some(param) = self.parse_fn_param() {
+                        params.push(param);
+                    }
+                },
+                None => error!(ParseError::RanOutTokens, span, self),
+            }
+        }
 
         let return_type = match self.buf.peek() {
-            Some((Token::CuBracketS, span)) => (
+            Some((Token::CuBracketS | Token::Semicolon(_), span)) => (
                 Type::BuiltIn(BuiltInType::Unit),
-                Span {
-                    start: span.start,
-                    end: span.end,
-                },
+                self.span(span.start, span.end),
             ),
             Some(_) => {
                 unwrap_or_return_set_buf!(self.parse_type(), self.buf)
             },
-            _ => todo!(),
+            None => error!(ParseError::RanOutTokens, span, self),
         };
 
-        let (body, body_span) = if let Some(a) = self.parse_scope_impl(depth, NodeExtra::default())
-        {
-            a
-        } else {
-            return;
+        // `extern` functions can be declared without a body to link against external ones
+        let (body, body_span) = match self.buf.peek() {
+            Some((Token::Semicolon(_) | Token::CuBracketE, _)) | None => {
+                let decl_span = self.span(span.start, return_type.1.end);
+
+                if !matches!(link, Some((Linkage::External, _))) {
+                    self.errs
+                        .push((ParseError::MissingFunctionBody, decl_span.clone()));
+                }
+
+                if matches!(self.buf.peek(), Some((Token::Semicolon(_), _))) {
+                    self.buf.next();
+                }
+
+                (None, decl_span)
+            },
+            Some(_) => {
+                if let Some((body, body_span)) = self.parse_scope_impl(depth, NodeExtra::default())
+                {
+                    (Some(Box::new(body)), body_span)
+                } else {
+                    return;
+                }
+            },
         };
-        let body = Box::new(body);
 
         ast.push(Node {
             kind: NodeKind::FunctionDeclare {
@@ -486,18 +640,416 @@ impl<'a> Parser<'a> {
                 link,
                 ident,
                 params,
+                is_variadic,
                 return_type,
                 body,
                 span: body_span.clone(),
             },
-            span: Span {
-                start: span.start,
-                end: body_span.end,
+            span: self.span(span.start, body_span.end),
+            extra,
+        });
+    }
+
+    fn parse_struct(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        depth: usize,
+    ) {
+        vis!(root vis, self, depth);
+        link!(disable link, self);
+
+        let span = self.buf.next().unwrap().1.clone();
+
+        let ident = unwrap_ident!(self);
+        assert_token!(Token::CuBracketS, "start of struct body", self);
+
+        // fields are separated by commas or newlines
+        let mut fields = Vec::new();
+        let end = loop {
+            match self.buf.next() {
+                Some((Token::CuBracketE, s)) => break s.end,
+                Some((Token::Comma | Token::Semicolon(_), _)) => {},
+                Some((Token::Ident, s)) => {
+                    let name = (self.src[s.start..s.end].to_string(), s.clone());
+                    let typ = unwrap_or_return_set_buf!(self.parse_type(), self.buf);
+                    fields.push((name, typ));
+                },
+                Some((t, s)) => error!(
+                    ParseError::UnexpectedToken {
+                        expected: Some("field or end of struct body"),
+                        found: t.clone(),
+                    },
+                    s.clone(),
+                    self
+                ),
+                None => error!(ParseError::RanOutTokens, span, self),
+            }
+        };
+
+        ast.push(Node {
+            kind: NodeKind::StructDeclare { vis, ident, fields },
+            span: self.span(span.start, end),
+            extra,
+        });
+    }
+
+    fn parse_enum(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        depth: usize,
+    ) {
+        vis!(root vis, self, depth);
+        link!(disable link, self);
+
+        let span = self.buf.next().unwrap().1.clone();
+
+        let ident = unwrap_ident!(self);
+        assert_token!(Token::CuBracketS, "start of enum body", self);
+
+        let mut variants = Vec::new();
+        let end = loop {
+            match self.buf.next() {
+                Some((Token::CuBracketE, s)) => break s.end,
+                Some((Token::Comma | Token::Semicolon(_), _)) => {},
+                Some((Token::Ident, s)) => {
+                    let name = (self.src[s.start..s.end].to_string(), s.clone());
+
+                    let mut payload = Vec::new();
+                    if let Some((Token::RoBracketS, _)) = self.buf.peek() {
+                        self.buf.next();
+                        while !matches!(self.buf.peek(), Some((Token::RoBracketE, _))) {
+                            payload.push(unwrap_or_return_set_buf!(self.parse_type(), self.buf));
+
+                            match self.buf.peek() {
+                                Some((Token::Comma, _)) => self.buf.idx += 1,
+                                Some((Token::RoBracketE, _)) => {},
+                                Some((t, s)) => error!(
+                                    ParseError::UnexpectedToken {
+                                        expected: Some("comma or end of round bracket"),
+                                        found: t.clone(),
+                                    },
+                                    s.clone(),
+                                    self
+                                ),
+                                None => error!(ParseError::RanOutTokens, span, self),
+                            }
+                        }
+                        self.buf.next();
+                    }
+
+                    let discriminant = match self.buf.peek() {
+                        Some((Token::Operator(Operator::Assign), _)) => {
+                            self.buf.next();
+                            Some(unwrap_or_return_set_buf!(
+                                self.parse_discriminant(),
+                                self.buf
+                            ))
+                        },
+                        _ => None,
+                    };
+
+                    variants.push((name, payload, discriminant));
+                },
+                Some((t, s)) => error!(
+                    ParseError::UnexpectedToken {
+                        expected: Some("variant or end of enum body"),
+                        found: t.clone(),
+                    },
+                    s.clone(),
+                    self
+                ),
+                None => error!(ParseError::RanOutTokens, span, self),
+            }
+        };
+
+        ast.push(Node {
+            kind: NodeKind::EnumDeclare {
+                vis,
+                ident,
+                variants,
+            },
+            span: self.span(span.start, end),
+            extra,
+        });
+    }
+
+    fn parse_mod(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        depth: usize,
+    ) {
+        vis!(root vis, self, depth);
+        link!(disable link, self);
+
+        let span = self.buf.next().unwrap().1.clone();
+
+        let ident = unwrap_ident!(self);
+
+        // `mod name` without a body is loaded from `name.kr` by the driver
+        if !matches!(self.buf.peek(), Some((Token::CuBracketS, _))) {
+            let end = ident.1.end;
+            ast.push(Node {
+                kind: NodeKind::Module {
+                    vis,
+                    ident,
+                    body: None,
+                },
+                span: self.span(span.start, end),
+                extra,
+            });
+            return;
+        }
+        self.buf.next();
+
+        // items of a module are parsed like the ones in the root
+        let mut body = UntypedAst::new();
+        self.parse_more(&mut body, 0);
+
+        let end = match self.buf.next() {
+            Some((Token::CuBracketE, s)) => s.end,
+            _ => error!(
+                ParseError::UnendedScope,
+                self.span(span.start, self.last_token().unwrap().1.end),
+                self
+            ),
+        };
+
+        for n in body.iter() {
+            if !n.kind.is_item() {
+                self.errs.push((ParseError::ExpectedItem, n.span.clone()));
+            }
+        }
+
+        ast.push(Node {
+            kind: NodeKind::Module {
+                vis,
+                ident,
+                body: Some(body),
             },
+            span: self.span(span.start, end),
+            extra,
+        });
+    }
+
+    fn parse_use(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        _depth: usize,
+    ) {
+        vis!(disable vis, self);
+        link!(disable link, self);
+
+        let span = self.buf.next().unwrap().1.clone();
+
+        let mut path = vec![unwrap_ident!(self)];
+        while let Some((Token::ModSep, _)) = self.buf.peek() {
+            self.buf.next();
+            path.push(unwrap_ident!(self));
+        }
+
+        let end = path.last().unwrap().1.end;
+        ast.push(Node {
+            kind: NodeKind::Use(path),
+            span: self.span(span.start, end),
             extra,
         });
     }
 
+    fn parse_discriminant(&mut self) -> Option<Annotated<i128>> {
+        // the lexer can't tell unary from binary minus, that's up to the expression parser
+        let negative = match self.buf.peek() {
+            Some((Token::Operator(Operator::Sub | Operator::Minus), s)) => {
+                let start = s.start;
+                self.buf.next();
+                Some(start)
+            },
+            _ => None,
+        };
+
+        match self.buf.next() {
+            // too large values saturate, they're out of range for the tag either way
+            Some((Token::Integer((v, None)), s)) => {
+                let (v, end) = (i128::try_from(*v).unwrap_or(i128::MAX), s.end);
+                let s = s.clone();
+                Some(negative.map_or_else(|| (v, s), |start| (-v, self.span(start, end))))
+            },
+            Some((t, s)) => {
+                self.errs.push((
+                    ParseError::UnexpectedToken {
+                        expected: Some("integer"),
+                        found: t.clone(),
+                    },
+                    s.clone(),
+                ));
+                None
+            },
+            None => {
+                self.errs.push((
+                    ParseError::RanOutTokens,
+                    self.last_token().unwrap().1.clone(),
+                ));
+                None
+            },
+        }
+    }
+
+    fn parse_match(
+        &mut self,
+        ast: &mut UntypedAst,
+        vis: Option<(Visibility, Span)>,
+        link: Option<(Linkage, Span)>,
+        extra: NodeExtra,
+        depth: usize,
+    ) {
+        vis!(disable vis, self);
+        link!(disable link, self);
+
+        let span = self.buf.next().unwrap().1.clone();
+
+        let expr = if let Some(expr) = self.parse_cond_expr() {
+            expr
+        } else {
+            return;
+        };
+
+        assert_token!(Token::CuBracketS, "start of match body", self);
+
+        let mut arms = Vec::new();
+        let end = loop {
+            match self.buf.peek() {
+                Some((Token::CuBracketE, s)) => {
+                    let end = s.end;
+                    self.buf.next();
+                    break end;
+                },
+                Some((Token::Comma | Token::Semicolon(_), _)) => {
+                    self.buf.next();
+                },
+                Some(_) => {
+                    let (pattern, start) =
+                        unwrap_or_return_set_buf!(self.parse_pattern(), self.buf);
+                    assert_token!(Token::FatArrow, "`=>`", self);
+
+                    let (body, body_span) =
+                        if let Some(a) = self.parse_scope_impl(depth, NodeExtra::default()) {
+                            a
+                        } else {
+                            return;
+                        };
+
+                    arms.push((pattern, Box::new(body), self.span(start, body_span.end)));
+                },
+                None => error!(ParseError::UnendedScope, span, self),
+            }
+        };
+
+        ast.push(Node {
+            kind: NodeKind::Match { expr, arms },
+            span: self.span(span.start, end),
+            extra,
+        });
+    }
+
+    /// Returns the pattern and where it starts
+    fn parse_pattern(&mut self) -> Option<(Pattern<AIdent, AString>, usize)> {
+        let mut segs = Vec::new();
+        loop {
+            match self.buf.next() {
+                Some((Token::Ident, s)) => {
+                    segs.push((self.src[s.start..s.end].to_string(), s.clone()))
+                },
+                Some((t, s)) => {
+                    self.errs.push((
+                        ParseError::UnexpectedToken {
+                            expected: Some("pattern"),
+                            found: t.clone(),
+                        },
+                        s.clone(),
+                    ));
+                    return None;
+                },
+                None => {
+                    self.errs.push((
+                        ParseError::RanOutTokens,
+                        self.last_token().unwrap().1.clone(),
+                    ));
+                    return None;
+                },
+            }
+
+            match self.buf.peek() {
+                Some((Token::ModSep, _)) => self.buf.idx += 1,
+                _ => break,
+            }
+        }
+
+        let start = segs[0].1.start;
+
+        if segs.len() == 1 && !matches!(self.buf.peek(), Some((Token::RoBracketS, _))) {
+            let ident = segs.pop().unwrap();
+            return Some((
+                if ident.0 == "_" {
+                    Pattern::Wildcard
+                } else {
+                    Pattern::Binding(ident)
+                },
+                start,
+            ));
+        }
+
+        let mut bindings = Vec::new();
+        if let Some((Token::RoBracketS, _)) = self.buf.peek() {
+            self.buf.next();
+            loop {
+                match self.buf.next() {
+                    Some((Token::RoBracketE, _)) => break,
+                    Some((Token::Comma, _)) => {},
+                    Some((Token::Ident, s)) => {
+                        let name = self.src[s.start..s.end].to_string();
+                        bindings.push((name != "_").then(|| (name, s.clone())));
+                    },
+                    Some((t, s)) => {
+                        self.errs.push((
+                            ParseError::UnexpectedToken {
+                                expected: Some("binding or end of round bracket"),
+                                found: t.clone(),
+                            },
+                            s.clone(),
+                        ));
+                        return None;
+                    },
+                    None => {
+                        self.errs.push((
+                            ParseError::UnendedBracket,
+                            self.last_token().unwrap().1.clone(),
+                        ));
+                        return None;
+                    },
+                }
+            }
+        }
+
+        Some((
+            Pattern::Variant {
+                ident: segs,
+                bindings,
+            },
+            start,
+        ))
+    }
+
     fn parse_fn_param(&mut self) -> Option<(AString, AType, Span)> {
         let ident = match self.buf.next() {
             Some((Token::Ident, s)) => (self.src[s.start..s.end].to_string(), s.clone()),
@@ -543,6 +1095,6 @@ impl<'a> Parser<'a> {
         let start = ident.1.start;
         let end = typ.1.end;
 
-        Some((ident, typ, Span { start, end }))
+        Some((ident, typ, self.span(start, end)))
     }
 }
@@ -4,15 +4,42 @@ impl<'a> Parser<'a> {
     pub(super) fn parse_type(&mut self) -> Option<AType> {
         match self.buf.next() {
             Some((Token::Ident, span)) => {
-                let typ = Type::from_str(self.src.slice(span.clone()).unwrap());
-                Some((typ, span.clone()))
+                let mut span = span.clone();
+
+                // paths to types in other modules, the numerator splits them up again
+                while let Some((Token::ModSep, _)) = self.buf.peek() {
+                    self.buf.next();
+                    match self.buf.next() {
+                        Some((Token::Ident, s)) => span.end = s.end,
+                        Some((t, s)) => {
+                            self.errs.push((
+                                ParseError::UnexpectedToken {
+                                    expected: Some("identifier"),
+                                    found: t.clone(),
+                                },
+                                s.clone(),
+                            ));
+                            return None;
+                        },
+                        None => {
+                            self.errs.push((
+                                ParseError::RanOutTokens,
+                                self.last_token().unwrap().1.clone(),
+                            ));
+                            return None;
+                        },
+                    }
+                }
+
+                let typ = Type::from_str(self.src.slice(span.range()).unwrap());
+                Some((typ, span))
             },
             Some((Token::Operator(Operator::And), Span { start, .. })) => {
                 let start = *start;
                 let inner = self.parse_type();
                 inner.map(|inner| {
                     let end = inner.1.end;
-                    (Type::Pointer(Box::new(inner)), start..end)
+                    (Type::Pointer(Box::new(inner)), self.span(start, end))
                 })
             },
             Some((Token::Operator(Operator::AndAnd), span)) => {
@@ -21,8 +48,11 @@ impl<'a> Parser<'a> {
                 let inner = self.parse_type()?;
                 let end = inner.1.end;
                 Some((
-                    Type::Pointer(Box::new((Type::Pointer(Box::new(inner)), start_2..end))),
-                    start_1..end,
+                    Type::Pointer(Box::new((
+                        Type::Pointer(Box::new(inner)),
+                        self.span(start_2, end),
+                    ))),
+                    self.span(start_1, end),
                 ))
             },
             Some((Token::SqBracketS, Span { start, .. })) => {
@@ -31,11 +61,12 @@ impl<'a> Parser<'a> {
                 let inner = self.parse_type()?;
                 match self.buf.next() {
                     Some((Token::SqBracketE, end_span)) => {
-                        Some((Type::Slice(Box::new(inner)), start..end_span.end))
+                        let end = end_span.end;
+                        Some((Type::Slice(Box::new(inner)), self.span(start, end)))
                     },
                     Some((Token::Operator(Operator::Mlt), _)) => {
                         let size = match self.buf.next() {
-                            Some((Token::Integer(size), span)) => (*size, span.clone()),
+                            Some((Token::Integer((size, _)), span)) => (*size, span.clone()),
                             Some((t, span)) => {
                                 self.errs.push((
                                     ParseError::UnexpectedToken {
@@ -80,7 +111,7 @@ impl<'a> Parser<'a> {
                             },
                         };
 
-                        Some((Type::Array(Box::new(inner), size), start..end))
+                        Some((Type::Array(Box::new(inner), size), self.span(start, end)))
                     },
                     Some((t, span)) => {
                         self.errs.push((
@@ -125,6 +156,7 @@ impl<'a> Parser<'a> {
 
                 let end;
                 let mut args = Vec::new();
+                let mut is_variadic = false;
 
                 loop {
                     match self.buf.peek() {
@@ -133,6 +165,13 @@ impl<'a> Parser<'a> {
                             self.buf.next();
                             break;
                         },
+                        Some((Token::Ellipsis, _)) => {
+                            self.buf.next();
+                            is_variadic = true;
+                            end =
+                                assert_token!(Token::RoBracketE, "end of argument list", self).end;
+                            break;
+                        },
                         Some(_) => {},
                         None => {
                             let prev = self.buf.prev().map_or_else(Span::default, |a| a.1.clone());
@@ -170,14 +209,18 @@ impl<'a> Parser<'a> {
                     Some((Token::Ident | Token::Fn | Token::RoBracketS | Token::SqBracketS, _)) => {
                         let ret = self.parse_type()?;
                         let end = ret.1.end;
-                        Some((Type::Function(args, Box::new(ret)), start..end))
+                        Some((
+                            Type::Function(args, Box::new(ret), is_variadic),
+                            self.span(start, end),
+                        ))
                     },
                     _ => Some((
                         Type::Function(
                             args,
-                            Box::new((Type::BuiltIn(BuiltInType::Unit), start..end)),
+                            Box::new((Type::BuiltIn(BuiltInType::Unit), self.span(start, end))),
+                            is_variadic,
                         ),
-                        start..end,
+                        self.span(start, end),
                     )),
                 }
             },
@@ -185,7 +228,10 @@ impl<'a> Parser<'a> {
                 let start = span.start;
                 let typ = self.parse_type()?;
                 match self.buf.next() {
-                    Some((Token::RoBracketE, span)) => Some((typ.0, start..span.end)),
+                    Some((Token::RoBracketE, span)) => {
+                        let end = span.end;
+                        Some((typ.0, self.span(start, end)))
+                    },
                     Some((t, span)) => {
                         self.errs.push((
                             ParseError::UnexpectedToken {
@@ -1,12 +1,28 @@
 use super::*;
-
-pub fn typecheck(ast: &NumeratedAst, count: usize) -> (Vec<AType>, Vec<AError<TypeCheckError>>) {
+use std::collections::HashMap;
+
+/// `ptr_bits` is the pointer width of the target, which `int` and `uint` have
+pub fn typecheck(
+    ast: &NumeratedAst,
+    count: usize,
+    ptr_bits: u32,
+) -> (Vec<AType>, Vec<AError<TypeCheckError>>) {
     let mut typechecker = Typechecker {
-        types: vec![CheckingBaseType::Any.expand(0..0); count],
+        types: vec![CheckingBaseType::Any.expand(Span::default()); count],
         errs: Vec::new(),
+        bitwise: Vec::new(),
+        literals: Vec::new(),
+        structs: HashMap::new(),
+        enums: HashMap::new(),
+        variants: HashMap::new(),
+        fields: Vec::new(),
+        casts: Vec::new(),
+        operators: Vec::new(),
+        ptr_bits,
     };
 
     typechecker.typecheck_ast(ast, None);
+    typechecker.check_recursive_types();
     typechecker.finalize(count)
 }
 
@@ -14,32 +30,306 @@ pub fn typecheck(ast: &NumeratedAst, count: usize) -> (Vec<AType>, Vec<AError<Ty
 struct Typechecker {
     types: Vec<CheckingType>,
     errs: Vec<AError<TypeCheckError>>,
+    /// Operands of bitwise operators, checked to not be floats once all types are known
+    bitwise: Vec<(usize, Span)>,
+    /// Integer literals and whether they're negated, checked to fit into their type once all
+    /// types are known
+    literals: Vec<(usize, u128, bool, Span)>,
+    /// Names and fields of declared structs by the id of their declaration
+    structs: HashMap<usize, (String, Vec<(AString, AType)>)>,
+    /// Names and variants of declared enums by the id of their declaration
+    enums: HashMap<usize, (String, Vec<Variant<NString>>)>,
+    /// The enum and the index of each variant by its id
+    variants: HashMap<usize, (usize, usize)>,
+    /// Field accesses, resolved once the type of the accessed expression is known
+    fields: Vec<(usize, usize, AString)>,
+    /// Operands and results of casts, checked to be a legal conversion once all types are known
+    casts: Vec<(usize, usize, Span)>,
+    /// Arithmetic, bitwise and comparison operators and their operand, checked to be applicable
+    /// to the operand once all types are known
+    operators: Vec<(Operator, usize, Span)>,
+    /// Width of `int` and `uint`
+    ptr_bits: u32,
 }
 
 impl Typechecker {
     fn finalize(mut self, count: usize) -> (Vec<AType>, Vec<AError<TypeCheckError>>) {
-        println!("{:#?}", self.types);
-
         for i in 0..self.types.len() {
             self.finalize_id(i);
         }
 
-        println!("{:#?}", self.types);
+        // resolving a field can make the type of another accessed expression known
+        while self.resolve_fields() {
+            for i in 0..self.types.len() {
+                self.finalize_id(i);
+            }
+        }
+
+        self.narrow_numbers();
+        self.check_array_lengths();
+
+        for (id, span) in std::mem::take(&mut self.bitwise) {
+            if matches!(
+                self.types[id].base,
+                CheckingBaseType::BuiltIn(BuiltInType::F32 | BuiltInType::F64)
+                    | CheckingBaseType::Float
+            ) {
+                self.errs.push((TypeCheckError::FloatBitwise, span));
+            }
+        }
+
+        for (id, value, negated, span) in std::mem::take(&mut self.literals) {
+            let typ = match &self.types[id].base {
+                CheckingBaseType::BuiltIn(b) => b.clone(),
+                CheckingBaseType::Integer => BuiltInType::Int,
+                CheckingBaseType::UnsignedInteger => BuiltInType::Uint,
+                _ => continue,
+            };
+
+            if let Some((min, max)) = int_range(&typ, self.ptr_bits) {
+                let fits = if negated {
+                    value <= min.unsigned_abs()
+                } else {
+                    value <= max
+                };
+
+                if !fits {
+                    self.errs.push((
+                        TypeCheckError::IntegerOutOfRange {
+                            typ: typ.to_string(),
+                            min,
+                            max,
+                        },
+                        span,
+                    ));
+                }
+            }
+        }
 
         let mut types = Vec::with_capacity(count);
         for i in 0..count {
             types.push(self.output_type(i));
         }
 
+        for (from, to, span) in std::mem::take(&mut self.casts) {
+            let fieldless = |e: usize| self.enums[&e].1.iter().all(|v| v.1.is_empty());
+            if !cast_allowed(&types[from].0, &types[to].0, fieldless) {
+                self.errs.push((
+                    TypeCheckError::InvalidCast {
+                        from: types[from].0.to_string(),
+                        to: types[to].0.to_string(),
+                    },
+                    span,
+                ));
+            }
+        }
+
+        for (op, id, span) in std::mem::take(&mut self.operators) {
+            if !operand_allowed(&op, &types[id].0) {
+                self.errs.push((
+                    TypeCheckError::InvalidOperand {
+                        op: op.symbol().to_string(),
+                        typ: types[id].0.to_string(),
+                    },
+                    span,
+                ));
+            }
+        }
+
         (types, self.errs)
     }
 
     fn typecheck_ast(&mut self, ast: &NumeratedAst, ret: Option<usize>) {
+        self.declare_types(ast);
+
         for n in ast.iter() {
             self.typecheck_node(n, ret);
         }
     }
 
+    /// Registers the structs and enums declared in a scope, including the ones in its modules
+    fn declare_types(&mut self, ast: &NumeratedAst) {
+        for n in ast.iter() {
+            match &n.kind {
+                NodeKind::StructDeclare { ident, fields, .. } => {
+                    self.structs
+                        .insert(ident.1.1, (ident.0.clone(), fields.clone()));
+                },
+                NodeKind::EnumDeclare {
+                    ident, variants, ..
+                } => {
+                    for (i, v) in variants.iter().enumerate() {
+                        self.variants.insert(v.0.1.1, (ident.1.1, i));
+                    }
+                    self.enums
+                        .insert(ident.1.1, (ident.0.clone(), variants.clone()));
+                },
+                NodeKind::Module {
+                    body: Some(body), ..
+                } => self.declare_types(body),
+                _ => {},
+            }
+        }
+    }
+
+    /// Reports array lengths LLVM can't represent, each written length once no matter how many
+    /// types were derived from it
+    fn check_array_lengths(&mut self) {
+        // fields and payloads of unused types are still laid out
+        let mut lengths = Vec::new();
+        for (_, fields) in self.structs.values() {
+            for (_, t) in fields.iter() {
+                array_lengths(&t.0, &mut lengths);
+            }
+        }
+        for (_, variants) in self.enums.values() {
+            for t in variants.iter().flat_map(|v| v.1.iter()) {
+                array_lengths(&t.0, &mut lengths);
+            }
+        }
+        lengths.extend(self.types.iter().filter_map(|t| match &t.base {
+            CheckingBaseType::Array(_, len) => Some(len.clone()),
+            _ => None,
+        }));
+
+        lengths.retain(|l| u32::try_from(l.0).is_err());
+        lengths.sort_by_key(|l| (l.1.file, l.1.start, l.1.end));
+        lengths.dedup_by(|a, b| a.1 == b.1);
+
+        for (len, span) in lengths {
+            self.errs.push((TypeCheckError::ArrayTooLarge(len), span));
+        }
+    }
+
+    /// Reports structs and enums that contain themselves without a pointer in between, they'd be
+    /// infinitely large
+    fn check_recursive_types(&mut self) {
+        let mut ids = self
+            .structs
+            .keys()
+            .chain(self.enums.keys())
+            .copied()
+            .collect::<Vec<usize>>();
+        ids.sort_unstable();
+
+        for id in ids {
+            let recursive = self
+                .contained_types(id)
+                .into_iter()
+                .find(|t| self.contains_type(&t.0, id, &mut vec![]));
+
+            if let Some(t) = recursive {
+                let name = self
+                    .structs
+                    .get(&id)
+                    .map_or_else(|| self.enums[&id].0.clone(), |s| s.0.clone());
+                self.errs.push((TypeCheckError::RecursiveType(name), t.1));
+            }
+        }
+    }
+
+    /// The types a struct or the payloads of an enum hold by value
+    fn contained_types(&self, id: usize) -> Vec<AType> {
+        match (self.structs.get(&id), self.enums.get(&id)) {
+            (Some(s), _) => s.1.iter().map(|f| f.1.clone()).collect(),
+            (_, Some(e)) => e.1.iter().flat_map(|v| v.1.iter().cloned()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a value of type `t` holds the struct or enum `id` without a pointer in between
+    fn contains_type(&self, t: &Type, id: usize, seen: &mut Vec<usize>) -> bool {
+        match t {
+            Type::Struct(_, s) | Type::Enum(_, s) if *s == id => true,
+            Type::Struct(_, s) | Type::Enum(_, s) if !seen.contains(s) => {
+                seen.push(*s);
+                self.contained_types(*s)
+                    .iter()
+                    .any(|c| self.contains_type(&c.0, id, seen))
+            },
+            Type::Array(t, _) => self.contains_type(&t.0, id, seen),
+            _ => false,
+        }
+    }
+
+    /// Gives the accessed fields whose struct is known their type, returns whether any were
+    fn resolve_fields(&mut self) -> bool {
+        let mut progress = false;
+
+        for (id, base, field) in std::mem::take(&mut self.fields) {
+            let sid = match &self.types[base].base {
+                CheckingBaseType::Struct(sid) => *sid,
+                CheckingBaseType::Any => {
+                    self.fields.push((id, base, field));
+                    continue;
+                },
+                CheckingBaseType::Error => continue,
+                _ => {
+                    self.errs.push((
+                        TypeCheckError::UnknownField {
+                            field: field.0,
+                            typ: self.format_id(base),
+                        },
+                        field.1,
+                    ));
+                    continue;
+                },
+            };
+
+            let typ = self.structs[&sid]
+                .1
+                .iter()
+                .find(|f| f.0.0 == field.0)
+                .map(|f| f.1.clone());
+
+            if let Some(typ) = typ {
+                let t = self.id_from_atype(&typ);
+                self.enforce(t);
+                self.link(id, t);
+                progress = true;
+            } else {
+                self.errs.push((
+                    TypeCheckError::UnknownField {
+                        field: field.0,
+                        typ: self.structs[&sid].0.clone(),
+                    },
+                    field.1,
+                ));
+            }
+        }
+
+        progress
+    }
+
+    /// Elements of pointers, slices and arrays are constrained after the ids linked to them were
+    /// finalized, numbers left generic take the type of an element they're linked to
+    fn narrow_numbers(&mut self) {
+        let mut progress = true;
+        while progress {
+            progress = false;
+
+            for id in 0..self.types.len() {
+                let fits = match self.types[id].base {
+                    CheckingBaseType::Integer => CheckingBaseType::is_int,
+                    CheckingBaseType::UnsignedInteger => CheckingBaseType::is_uint,
+                    CheckingBaseType::Float => CheckingBaseType::is_float,
+                    _ => continue,
+                };
+
+                let t = &self.types[id];
+                let known = t.links_to.iter().chain(t.linked_from.iter()).find(|l| {
+                    let base = &self.types[**l].base;
+                    matches!(base, CheckingBaseType::BuiltIn(_)) && fits(base)
+                });
+
+                if let Some(known) = known {
+                    self.types[id].base = self.types[*known].base.clone();
+                    progress = true;
+                }
+            }
+        }
+    }
+
     fn finalize_id(&mut self, id: usize) { self._finalize_single(id, &mut vec![]); }
 
     fn _finalize_single(&mut self, id: usize, hist: &mut Vec<usize>) {
@@ -93,7 +383,7 @@ impl Typechecker {
     }
 
     fn def_in(&mut self, id: usize, span: Span) {
-        if span != (0..0) && self.types[id].derived_from == (0..0) {
+        if span != Span::default() && self.types[id].derived_from == Span::default() {
             self.types[id].derived_from = span;
         }
     }
@@ -114,7 +404,7 @@ impl Typechecker {
             CheckingBaseType::Slice(s)
             | CheckingBaseType::Array(s, _)
             | CheckingBaseType::Pointer(s) => self.recursive_error(*s),
-            CheckingBaseType::Function(a, r) => {
+            CheckingBaseType::Function(a, r, _) => {
                 let r = *r;
 
                 for a in a.clone().iter() {
@@ -127,7 +417,10 @@ impl Typechecker {
             | CheckingBaseType::Any
             | CheckingBaseType::Error
             | CheckingBaseType::Integer
-            | CheckingBaseType::UnsignedInteger => {},
+            | CheckingBaseType::UnsignedInteger
+            | CheckingBaseType::Float
+            | CheckingBaseType::Struct(_)
+            | CheckingBaseType::Enum(_) => {},
         }
 
         if !self.types[id].is_forced {
@@ -154,6 +447,7 @@ impl Typechecker {
                 }
             },
             NodeKind::Expr(expr) => self.typecheck_expr(expr),
+            NodeKind::Break(_) | NodeKind::Continue(_) => {},
             NodeKind::Scope { body, .. } => self.typecheck_ast(body, ret),
             NodeKind::If { main, els } => {
                 self.typecheck_expr(&main.0);
@@ -168,7 +462,8 @@ impl Typechecker {
                     self.typecheck_node(&els.0, ret);
                 }
             },
-            NodeKind::While { cond, body } => {
+            NodeKind::While { label, cond, body } => {
+                self.typecheck_label(label);
                 self.typecheck_expr(cond);
                 let b = self.id_from_type(
                     CheckingBaseType::BuiltIn(BuiltInType::Bool).expand(n.span.clone()),
@@ -177,6 +472,154 @@ impl Typechecker {
                 self.link(b, cond.1.1);
                 self.typecheck_node(body, ret);
             },
+            NodeKind::For {
+                label,
+                ident,
+                range,
+                body,
+            } => {
+                self.typecheck_label(label);
+                self.def_in(ident.1.1, ident.1.0.clone());
+                self.typecheck_expr(&range.0);
+                self.typecheck_expr(&range.1);
+
+                let int = self.id_from_type(CheckingBaseType::Integer.expand(n.span.clone()));
+                self.link(int, range.0.1.1);
+                self.link(range.0.1.1, range.1.1.1);
+                self.link(range.1.1.1, range.0.1.1);
+                self.link(ident.1.1, range.0.1.1);
+
+                self.typecheck_node(body, ret);
+            },
+            NodeKind::StructDeclare { ident, .. } => {
+                // the name isn't a value but still has an id, give it a type so it's resolved
+                let unit = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Unit).expand(ident.1.0.clone()),
+                );
+                self.link(ident.1.1, unit);
+            },
+            NodeKind::Module { ident, body, .. } => {
+                let unit = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Unit).expand(ident.1.0.clone()),
+                );
+                self.link(ident.1.1, unit);
+
+                // its types were declared along with the enclosing scope's
+                for n in body.iter().flatten() {
+                    self.typecheck_node(n, None);
+                }
+            },
+            NodeKind::Use(_) => {},
+            NodeKind::EnumDeclare {
+                ident, variants, ..
+            } => {
+                let unit = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Unit).expand(ident.1.0.clone()),
+                );
+                self.link(ident.1.1, unit);
+
+                let (min, max) = int_range(&BuiltInType::I32, self.ptr_bits).unwrap();
+                for (v, d) in variants.iter().zip(discriminants(variants)) {
+                    self.link(v.0.1.1, unit);
+
+                    if d < min || d > max as i128 {
+                        self.errs.push((
+                            TypeCheckError::IntegerOutOfRange {
+                                typ: BuiltInType::I32.to_string(),
+                                min,
+                                max,
+                            },
+                            v.2.as_ref()
+                                .map_or_else(|| v.0.1.0.clone(), |d| d.1.clone()),
+                        ));
+                    }
+                }
+            },
+            NodeKind::Match { expr, arms } => {
+                self.typecheck_expr(expr);
+
+                let mut enm = None;
+                // matched variants and catch-all arms along with the span of the arm
+                let mut covered: Vec<((usize, usize), Span)> = Vec::new();
+                let mut catch_all: Option<Span> = None;
+                let mut mixed = false;
+                for (pattern, body, arm_span) in arms.iter() {
+                    let earlier = match pattern {
+                        _ if catch_all.is_some() => catch_all.clone(),
+                        Pattern::Variant { ident, .. } => covered
+                            .iter()
+                            .find(|c| c.0 == self.variants[&ident.1])
+                            .map(|c| c.1.clone()),
+                        _ => None,
+                    };
+                    if let Some(because) = earlier {
+                        self.errs
+                            .push((TypeCheckError::UnreachableArm { because }, arm_span.clone()));
+                    }
+
+                    match pattern {
+                        Pattern::Wildcard => {
+                            catch_all.get_or_insert_with(|| arm_span.clone());
+                        },
+                        Pattern::Binding(b) => {
+                            catch_all.get_or_insert_with(|| arm_span.clone());
+                            self.def_in(b.1.1, b.1.0.clone());
+                            self.link(b.1.1, expr.1.1);
+                        },
+                        Pattern::Variant { ident, bindings } => {
+                            let (eid, v) = self.variants[&ident.1];
+                            let span = Span {
+                                start: ident.0[0].1.start,
+                                end: ident.0.last().unwrap().1.end,
+                                file: ident.0[0].1.file,
+                            };
+
+                            // variants of different enums are reported as mismatched types
+                            let e =
+                                self.id_from_type(CheckingBaseType::Enum(eid).expand(span.clone()));
+                            self.enforce(e);
+                            self.link(e, expr.1.1);
+                            mixed |= *enm.get_or_insert(eid) != eid;
+                            covered.push(((eid, v), arm_span.clone()));
+
+                            let payload = self.enums[&eid].1[v].1.clone();
+                            if bindings.len() != payload.len() {
+                                self.errs.push((
+                                    TypeCheckError::BindingCount {
+                                        variant: self.variant_name(eid, v),
+                                        expected: payload.len(),
+                                        found: bindings.len(),
+                                    },
+                                    span,
+                                ));
+                            }
+
+                            for (b, t) in bindings.iter().zip(payload.iter()) {
+                                if let Some(b) = b {
+                                    self.def_in(b.1.1, b.1.0.clone());
+                                    let t = self.id_from_atype(t);
+                                    self.enforce(t);
+                                    self.link(b.1.1, t);
+                                }
+                            }
+                        },
+                    }
+
+                    self.typecheck_node(body, ret);
+                }
+
+                if let (Some(eid), false) = (enm, catch_all.is_some() || mixed) {
+                    let missing = (0..self.enums[&eid].1.len())
+                        .filter(|v| !covered.iter().any(|c| c.0 == (eid, *v)))
+                        .map(|v| self.variant_name(eid, v))
+                        .collect::<Vec<String>>();
+
+                    if !missing.is_empty() {
+                        self.errs
+                            .push((TypeCheckError::NonExhaustiveMatch(missing), n.span.clone()));
+                    }
+                }
+            },
             NodeKind::Return(expr) => {
                 if let Some(ret) = ret {
                     let id = if let Some(expr) = expr {
@@ -198,6 +641,7 @@ impl Typechecker {
             NodeKind::FunctionDeclare {
                 ident,
                 params,
+                is_variadic,
                 return_type,
                 body,
                 span,
@@ -213,24 +657,77 @@ impl Typechecker {
 
                 let r = self.id_from_atype(return_type);
                 self.enforce(r);
-                let f = self.id_from_type(CheckingBaseType::Function(p, r).expand(span.clone()));
+                let f = self.id_from_type(
+                    CheckingBaseType::Function(p, r, *is_variadic).expand(span.clone()),
+                );
                 self.enforce(f);
                 self.enforce(ident.1.1);
                 self.link(ident.1.1, f);
 
-                self.typecheck_node(body, Some(r));
+                if let Some(body) = body {
+                    self.typecheck_node(body, Some(r));
+                }
             },
         }
     }
 
+    fn variant_name(&self, enm: usize, variant: usize) -> String {
+        let (name, variants) = &self.enums[&enm];
+        format!("{name}::{}", variants[variant].0.0)
+    }
+
+    fn typecheck_label(&mut self, label: &Option<NString>) {
+        // labels aren't values but still have an id, give them a type so they're resolved
+        if let Some(label) = label {
+            let unit = self.id_from_type(
+                CheckingBaseType::BuiltIn(BuiltInType::Unit).expand(label.1.0.clone()),
+            );
+            self.link(label.1.1, unit);
+        }
+    }
+
     fn typecheck_expr(&mut self, expr: &NExpr) {
         self.def_in(expr.1.1, expr.1.0.clone());
 
         match &expr.0 {
-            Expr::Integer(_) => {
-                let int = self.id_from_type(CheckingBaseType::Integer.expand(expr.1.0.clone()));
+            Expr::Integer(value, suffix) => {
+                self.literals
+                    .push((expr.1.1, *value, false, expr.1.0.clone()));
+
+                let int = self.id_from_type(
+                    suffix
+                        .clone()
+                        .map_or(CheckingBaseType::Integer, CheckingBaseType::BuiltIn)
+                        .expand(expr.1.0.clone()),
+                );
+                // a suffix pins the type like an annotation does
+                if suffix.is_some() {
+                    self.enforce(int);
+                }
                 self.link(expr.1.1, int);
             },
+            Expr::Float(_) => {
+                let float = self.id_from_type(CheckingBaseType::Float.expand(expr.1.0.clone()));
+                self.link(expr.1.1, float);
+            },
+            Expr::Bool(_) => {
+                let b = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Bool).expand(expr.1.0.clone()),
+                );
+                self.link(expr.1.1, b);
+            },
+            Expr::Str(_) => {
+                let s = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Str).expand(expr.1.0.clone()),
+                );
+                self.link(expr.1.1, s);
+            },
+            Expr::Char(_) => {
+                let c = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Char).expand(expr.1.0.clone()),
+                );
+                self.link(expr.1.1, c);
+            },
             Expr::Ident(id) => {
                 self.link(expr.1.1, id.1);
                 self.set_lvalue(expr.1.1);
@@ -254,15 +751,31 @@ impl Typechecker {
                     self.id_from_type(CheckingBaseType::Pointer(opr.1.1).expand(expr.1.0.clone()));
                 self.link(expr.1.1, pi);
             },
-            Expr::UnOp { opr, .. } => {
+            Expr::UnOp { opr, op } => {
                 self.typecheck_expr(opr);
+
+                if matches!(op, Operator::Not) {
+                    self.bitwise.push((opr.1.1, expr.1.0.clone()));
+                }
+                if !matches!(op, Operator::Plus) {
+                    self.operators.push((op.clone(), opr.1.1, expr.1.0.clone()));
+                }
+
+                // `-128i8` is in range even though `128i8` isn't
+                if let (Operator::Minus, Expr::Integer(..)) = (op, &opr.0) {
+                    if let Some(literal) = self.literals.iter_mut().find(|l| l.0 == opr.1.1) {
+                        literal.2 = true;
+                        literal.3 = expr.1.0.clone();
+                    }
+                }
+
                 self.link(expr.1.1, opr.1.1);
                 self.link(opr.1.1, expr.1.1);
             },
             Expr::BiOp {
                 lhs,
                 rhs,
-                op: Operator::Assign | Operator::OpAssign(_),
+                op: op @ (Operator::Assign | Operator::OpAssign(_)),
             } => {
                 self.typecheck_expr(lhs);
                 self.typecheck_expr(rhs);
@@ -270,6 +783,14 @@ impl Typechecker {
 
                 self.link(lhs.1.1, rhs.1.1);
 
+                if let Operator::OpAssign(op) = op {
+                    if is_bitwise(op) {
+                        self.bitwise.push((lhs.1.1, expr.1.0.clone()));
+                    }
+                    self.operators
+                        .push(((**op).clone(), lhs.1.1, expr.1.0.clone()));
+                }
+
                 let unit = self.id_from_type(
                     CheckingBaseType::BuiltIn(BuiltInType::Unit).expand(expr.1.0.clone()),
                 );
@@ -280,24 +801,38 @@ impl Typechecker {
                 lhs,
                 rhs,
                 op:
-                    Operator::Eq
+                    op @ (Operator::Eq
                     | Operator::NE
                     | Operator::GT
                     | Operator::GE
                     | Operator::LT
-                    | Operator::LE
-                    | Operator::AndAnd
-                    | Operator::OrOr,
+                    | Operator::LE),
             } => {
                 self.typecheck_expr(lhs);
                 self.typecheck_expr(rhs);
                 self.link(lhs.1.1, rhs.1.1);
+                self.operators.push((op.clone(), lhs.1.1, expr.1.0.clone()));
 
                 let b = self.id_from_type(
                     CheckingBaseType::BuiltIn(BuiltInType::Bool).expand(expr.1.0.clone()),
                 );
                 self.link(expr.1.1, b);
             },
+            Expr::BiOp {
+                lhs,
+                rhs,
+                op: Operator::AndAnd | Operator::OrOr,
+            } => {
+                self.typecheck_expr(lhs);
+                self.typecheck_expr(rhs);
+
+                let b = self.id_from_type(
+                    CheckingBaseType::BuiltIn(BuiltInType::Bool).expand(expr.1.0.clone()),
+                );
+                self.link(lhs.1.1, b);
+                self.link(rhs.1.1, b);
+                self.link(expr.1.1, b);
+            },
             Expr::BiOp {
                 lhs,
                 rhs,
@@ -317,9 +852,15 @@ impl Typechecker {
 
                 self.set_lvalue(expr.1.1);
             },
-            Expr::BiOp { lhs, rhs, op: _ } => {
+            Expr::BiOp { lhs, rhs, op } => {
                 self.typecheck_expr(lhs);
                 self.typecheck_expr(rhs);
+
+                if is_bitwise(op) {
+                    self.bitwise.push((lhs.1.1, expr.1.0.clone()));
+                }
+                self.operators.push((op.clone(), lhs.1.1, expr.1.0.clone()));
+
                 self.link(lhs.1.1, rhs.1.1);
                 self.link(rhs.1.1, lhs.1.1);
                 self.link(expr.1.1, lhs.1.1);
@@ -334,10 +875,153 @@ impl Typechecker {
 
                 self.typecheck_expr(id);
 
-                let t = self
-                    .id_from_type(CheckingBaseType::Function(a, expr.1.1).expand(expr.1.0.clone()));
+                let t = self.id_from_type(
+                    CheckingBaseType::Function(a, expr.1.1, false).expand(expr.1.0.clone()),
+                );
                 self.link(t, id.1.1);
             },
+            Expr::StructLiteral { ident, fields } => {
+                for (_, e) in fields.iter() {
+                    self.typecheck_expr(e);
+                }
+
+                let (name, decl) = if let Some(s) = self.structs.get(&ident.1) {
+                    s.clone()
+                } else {
+                    // not a struct, the numerator already reported it
+                    let err = self.id_from_type(CheckingBaseType::Error.expand(expr.1.0.clone()));
+                    self.link(expr.1.1, err);
+                    return;
+                };
+
+                let s =
+                    self.id_from_type(CheckingBaseType::Struct(ident.1).expand(expr.1.0.clone()));
+                self.enforce(s);
+                self.link(expr.1.1, s);
+
+                let mut seen: Vec<&str> = Vec::with_capacity(fields.len());
+                for (field, e) in fields.iter() {
+                    if seen.contains(&field.0.as_str()) {
+                        self.errs.push((
+                            TypeCheckError::DuplicateField(field.0.clone()),
+                            field.1.clone(),
+                        ));
+                        continue;
+                    }
+                    seen.push(&field.0);
+
+                    if let Some((_, typ)) = decl.iter().find(|f| f.0.0 == field.0) {
+                        let t = self.id_from_atype(typ);
+                        self.enforce(t);
+                        self.link(t, e.1.1);
+                    } else {
+                        self.errs.push((
+                            TypeCheckError::UnknownField {
+                                field: field.0.clone(),
+                                typ: name.clone(),
+                            },
+                            field.1.clone(),
+                        ));
+                    }
+                }
+
+                let missing = decl
+                    .iter()
+                    .filter(|f| !seen.contains(&f.0.0.as_str()))
+                    .map(|f| f.0.0.clone())
+                    .collect::<Vec<String>>();
+                if !missing.is_empty() {
+                    self.errs.push((
+                        TypeCheckError::MissingFields {
+                            fields: missing,
+                            typ: name,
+                        },
+                        expr.1.0.clone(),
+                    ));
+                }
+            },
+            Expr::Variant { ident, op } => {
+                let mut a = Vec::with_capacity(op.len());
+                for i in op.iter() {
+                    self.typecheck_expr(i);
+                    a.push(i.1.1);
+                }
+
+                // constructing a variant is checked like calling a function returning the enum
+                let (eid, v) = self.variants[&ident.1];
+                let payload = self.enums[&eid].1[v].1.clone();
+                let mut p = Vec::with_capacity(payload.len());
+                for t in payload.iter() {
+                    let t = self.id_from_atype(t);
+                    self.enforce(t);
+                    p.push(t);
+                }
+
+                let r = self.id_from_type(CheckingBaseType::Enum(eid).expand(expr.1.0.clone()));
+                self.enforce(r);
+                let f = self
+                    .id_from_type(CheckingBaseType::Function(p, r, false).expand(expr.1.0.clone()));
+                self.enforce(f);
+
+                let t = self.id_from_type(
+                    CheckingBaseType::Function(a, expr.1.1, false).expand(expr.1.0.clone()),
+                );
+                self.link(t, f);
+                self.link(expr.1.1, r);
+            },
+            Expr::ArrayLiteral(elements) => {
+                for e in elements.iter() {
+                    self.typecheck_expr(e);
+                }
+
+                // the elements share the type of the first one
+                let elem = elements.first().map_or_else(
+                    || self.id_from_type(CheckingBaseType::Any.expand(expr.1.0.clone())),
+                    |first| first.1.1,
+                );
+                for e in elements.iter().skip(1) {
+                    self.link(elem, e.1.1);
+                    self.link(e.1.1, elem);
+                }
+
+                let len = (elements.len() as u128, expr.1.0.clone());
+                let a =
+                    self.id_from_type(CheckingBaseType::Array(elem, len).expand(expr.1.0.clone()));
+                self.enforce(a);
+                self.link(expr.1.1, a);
+            },
+            Expr::RepeatLiteral { value, count } => {
+                self.typecheck_expr(value);
+
+                let elem = self.id_from_type(CheckingBaseType::Any.expand(expr.1.0.clone()));
+                self.link(elem, value.1.1);
+                self.link(value.1.1, elem);
+
+                let a = self.id_from_type(
+                    CheckingBaseType::Array(elem, count.clone()).expand(expr.1.0.clone()),
+                );
+                self.enforce(a);
+                self.link(expr.1.1, a);
+            },
+            Expr::Cast { expr: e, typ } => {
+                self.typecheck_expr(e);
+
+                // the operand keeps its own type, only the result is the target
+                let target = self.id_from_atype(typ);
+                self.enforce(target);
+                self.link(expr.1.1, target);
+
+                self.casts.push((e.1.1, expr.1.1, expr.1.0.clone()));
+            },
+            Expr::Field { expr: e, field } => {
+                self.typecheck_expr(e);
+
+                if self.types[e.1.1].is_lvalue {
+                    self.set_lvalue(expr.1.1);
+                }
+
+                self.fields.push((expr.1.1, e.1.1, field.clone()));
+            },
         }
     }
 }
@@ -358,14 +1042,19 @@ pub enum CheckingBaseType {
     Pointer(usize),
     Slice(usize),
     Array(usize, Annotated<u128>),
-    Function(Vec<usize>, usize),
+    Function(Vec<usize>, usize, bool),
 
     BuiltIn(BuiltInType),
+    /// The id of its declaration
+    Struct(usize),
+    /// The id of its declaration
+    Enum(usize),
 
     #[default]
     Any,
     Integer,
     UnsignedInteger,
+    Float,
 
     Error,
 }
@@ -392,6 +1081,12 @@ impl CheckingBaseType {
         )
     }
 
+    const fn is_float(&self) -> bool {
+        use BuiltInType::*;
+        use CheckingBaseType::*;
+        matches!(self, Any | Error | BuiltIn(F32 | F64) | Float)
+    }
+
     fn expand(self, from: Span) -> CheckingType {
         CheckingType {
             base: self,
@@ -415,16 +1110,20 @@ impl Typechecker {
             Type::Pointer(t) => CheckingBaseType::Pointer(self.id_from_atype(t)),
             Type::Slice(t) => CheckingBaseType::Slice(self.id_from_atype(t)),
             Type::Array(t, s) => CheckingBaseType::Array(self.id_from_atype(t), s.clone()),
-            Type::Function(a, r) => CheckingBaseType::Function(
+            Type::Function(a, r, v) => CheckingBaseType::Function(
                 a.iter().map(|a| self.id_from_atype(a)).collect(),
                 self.id_from_atype(r),
+                *v,
             ),
 
             Type::BuiltIn(t) => CheckingBaseType::BuiltIn(t.clone()),
+            Type::Struct(_, id) => CheckingBaseType::Struct(*id),
+            Type::Enum(_, id) => CheckingBaseType::Enum(*id),
 
             Type::Any => CheckingBaseType::Any,
 
-            Type::Unknown(t) => todo!("unknown type {t} to CheckingType"),
+            // the numerator resolves every name used as a type
+            Type::Unknown(t) => unreachable!("unknown type {t} to CheckingType"),
         }
         .expand(at.1.clone());
         self.id_from_type(t)
@@ -439,6 +1138,7 @@ impl Typechecker {
             CheckingBaseType::Any => 1000,
             CheckingBaseType::Integer => 12,
             CheckingBaseType::UnsignedInteger => 6,
+            CheckingBaseType::Float => 12,
             _ => 1,
         }
     }
@@ -471,9 +1171,11 @@ impl Typechecker {
             },
 
             (CheckingBaseType::BuiltIn(l), CheckingBaseType::BuiltIn(r)) => l == r,
+            (CheckingBaseType::Struct(l), CheckingBaseType::Struct(r)) => l == r,
+            (CheckingBaseType::Enum(l), CheckingBaseType::Enum(r)) => l == r,
 
-            (CheckingBaseType::Function(la, lr), CheckingBaseType::Function(ra, rr))
-                if la.len() == ra.len() =>
+            (CheckingBaseType::Function(la, lr, lv), CheckingBaseType::Function(ra, rr, rv))
+                if la.len() == ra.len() && lv == rv =>
             {
                 self.types_eq(*lr, *rr, hist.0)
                     && la
@@ -486,6 +1188,7 @@ impl Typechecker {
             (CheckingBaseType::Error, CheckingBaseType::Error) => true,
             (CheckingBaseType::Integer, CheckingBaseType::Integer) => true,
             (CheckingBaseType::UnsignedInteger, CheckingBaseType::UnsignedInteger) => true,
+            (CheckingBaseType::Float, CheckingBaseType::Float) => true,
             _ => false,
         }
     }
@@ -553,12 +1256,33 @@ impl Typechecker {
             (CheckingBaseType::Slice(l), CheckingBaseType::Slice(r)) => {
                 return self._constrain_ids(*l, *r, hist.0, base);
             },
-            (CheckingBaseType::Array(l, ls), CheckingBaseType::Array(r, rs)) if ls == rs => {
+            (CheckingBaseType::Array(l, ls), CheckingBaseType::Array(r, rs)) if ls.0 == rs.0 => {
                 return self._constrain_ids(*l, *r, hist.0, base);
             },
-            (CheckingBaseType::Function(lp, la), CheckingBaseType::Function(rp, ra))
-                if lp.len() == rp.len() =>
+            (CheckingBaseType::Array(_, ls), CheckingBaseType::Array(_, rs)) => {
+                self.errs.push((
+                    TypeCheckError::ArrayLengthMismatch {
+                        expected: ls.0,
+                        found: rs.0,
+                        because: ls.1.clone(),
+                    },
+                    rs.1.clone(),
+                ));
+
+                self.recursive_error(l);
+                self.recursive_error(r);
+
+                return Err(());
+            },
+            // arrays coerce to slices of their elements
+            (CheckingBaseType::Slice(l), CheckingBaseType::Array(r, _)) => {
+                return self._constrain_ids(*l, *r, hist.0, base);
+            },
+            (CheckingBaseType::Function(lp, la, lv), CheckingBaseType::Function(rp, ra, rv))
+                if arity_matches(lp.len(), *lv, rp.len(), *rv) =>
             {
+                // extra arguments to a variadic function are left unconstrained, `zip` stops at
+                // the fixed parameters
                 let la = *la;
                 let ra = *ra;
                 let base2 = hist.0.len();
@@ -593,6 +1317,16 @@ impl Typechecker {
             return Ok(());
         }
 
+        let l_anyfloat = matches!(self.types[l].base, CheckingBaseType::Float);
+        let r_anyfloat = matches!(self.types[r].base, CheckingBaseType::Float);
+        if (l_anyfloat && self.types[r].base.is_float())
+            || (r_anyfloat && self.types[l].base.is_float())
+        {
+            self.link(l, r);
+            set(self);
+            return Ok(());
+        }
+
         if hist.0.len() == base + 1 {
             self.errs.push((
                 TypeCheckError::TypeMismatch {
@@ -637,23 +1371,30 @@ impl Typechecker {
                 acc += &format!(" * {s}]");
             },
             CheckingBaseType::BuiltIn(b) => acc += &b.to_string(),
+            CheckingBaseType::Struct(id) => acc += &self.structs[id].0,
+            CheckingBaseType::Enum(id) => acc += &self.enums[id].0,
             CheckingBaseType::Any => acc += "_",
             CheckingBaseType::Error => acc += "{err}",
-            CheckingBaseType::Function(args, ret) => {
+            CheckingBaseType::Function(args, ret, is_variadic) => {
                 let ret = *ret;
-                acc += "(fn(";
-                acc += &args
+                let mut args = args
                     .clone()
                     .into_iter()
                     .map(|a| self.format_id(a))
-                    .collect::<Vec<String>>()
-                    .join(", ");
+                    .collect::<Vec<String>>();
+                if *is_variadic {
+                    args.push("...".to_string());
+                }
+
+                acc += "(fn(";
+                acc += &args.join(", ");
                 acc += ") ";
                 acc += &self.format_id(ret);
                 acc += ")";
             },
             CheckingBaseType::Integer => acc += "{int}",
             CheckingBaseType::UnsignedInteger => acc += "{uint}",
+            CheckingBaseType::Float => acc += "{float}",
         }
 
         acc
@@ -663,7 +1404,7 @@ impl Typechecker {
 
     fn _output_type(&mut self, ti: usize, hist: &mut Vec<usize>) -> AType {
         if hist.contains(&ti) {
-            return (Type::Any, 0..0);
+            return (Type::Any, Span::default());
         }
 
         hist.push(ti);
@@ -676,15 +1417,19 @@ impl Typechecker {
                     Type::Array(Box::new(self._output_type(t, hist)), s)
                 },
 
-                CheckingBaseType::Function(a, r) => Type::Function(
+                CheckingBaseType::Function(a, r, v) => Type::Function(
                     a.into_iter().map(|a| self._output_type(a, hist)).collect(),
                     Box::new(self._output_type(r, hist)),
+                    v,
                 ),
 
                 CheckingBaseType::BuiltIn(b) => Type::BuiltIn(b),
+                CheckingBaseType::Struct(id) => Type::Struct(self.structs[&id].0.clone(), id),
+                CheckingBaseType::Enum(id) => Type::Enum(self.enums[&id].0.clone(), id),
 
                 CheckingBaseType::Integer => Type::BuiltIn(BuiltInType::Int),
                 CheckingBaseType::UnsignedInteger => Type::BuiltIn(BuiltInType::Uint),
+                CheckingBaseType::Float => Type::BuiltIn(BuiltInType::F64),
                 CheckingBaseType::Any => {
                     // HACK: maybe error made it unable to properly link types
                     if !self
@@ -712,3 +1457,342 @@ impl Typechecker {
         r
     }
 }
+
+/// The smallest and largest values of an integer type, `int` and `uint` are `ptr_bits` wide
+const fn int_range(t: &BuiltInType, ptr_bits: u32) -> Option<(i128, u128)> {
+    use BuiltInType::*;
+    Some(match t {
+        I8 => (i8::MIN as i128, i8::MAX as u128),
+        I16 => (i16::MIN as i128, i16::MAX as u128),
+        I32 => (i32::MIN as i128, i32::MAX as u128),
+        I64 => (i64::MIN as i128, i64::MAX as u128),
+        Int => (-(1 << (ptr_bits - 1)), (1 << (ptr_bits - 1)) - 1),
+        I128 => (i128::MIN, i128::MAX as u128),
+        U8 => (0, u8::MAX as u128),
+        U16 => (0, u16::MAX as u128),
+        U32 => (0, u32::MAX as u128),
+        U64 => (0, u64::MAX as u128),
+        Uint => (0, (1 << ptr_bits) - 1),
+        U128 => (0, u128::MAX),
+        _ => return None,
+    })
+}
+
+/// Collects the lengths of the arrays in a written type
+fn array_lengths(t: &Type, out: &mut Vec<Annotated<u128>>) {
+    match t {
+        Type::Array(t, len) => {
+            out.push(len.clone());
+            array_lengths(&t.0, out);
+        },
+        Type::Pointer(t) | Type::Slice(t) => array_lengths(&t.0, out),
+        Type::Function(params, ret, _) => {
+            for p in params.iter() {
+                array_lengths(&p.0, out);
+            }
+            array_lengths(&ret.0, out);
+        },
+        _ => {},
+    }
+}
+
+/// Whether `from as to` is legal: conversions between numbers, `bool` and `char` to integers,
+/// `u8` to `char`, between pointers and integers, and enums to integers if none of their
+/// variants has a payload
+fn cast_allowed(from: &Type, to: &Type, fieldless: impl Fn(usize) -> bool) -> bool {
+    use BuiltInType::*;
+    // the width of `int` doesn't matter for whether a type is an integer
+    let is_int = |b: &BuiltInType| int_range(b, 64).is_some();
+    let is_number = |b: &BuiltInType| is_int(b) || matches!(b, F32 | F64);
+
+    match (from, to) {
+        // something else already went wrong
+        (Type::Any, _) | (_, Type::Any) => true,
+        (Type::BuiltIn(f), Type::BuiltIn(t)) => {
+            f == t
+                || (is_number(f) && is_number(t))
+                || (matches!(f, Bool | Char) && is_int(t))
+                || matches!((f, t), (U8, Char))
+        },
+        (Type::Pointer(_), Type::Pointer(_)) => true,
+        (Type::Pointer(_), Type::BuiltIn(b)) | (Type::BuiltIn(b), Type::Pointer(_)) => is_int(b),
+        (Type::Enum(_, e), Type::BuiltIn(b)) => is_int(b) && fieldless(*e),
+        (Type::Struct(_, f), Type::Struct(_, t)) | (Type::Enum(_, f), Type::Enum(_, t)) => f == t,
+        (f, t) => f.to_string() == t.to_string(),
+    }
+}
+
+/// Whether `op` can be applied to an operand of type `t`: arithmetic to numbers, bitwise
+/// operators to integers and `bool`, and comparisons to numbers, `char` and pointers, with
+/// equality also comparing `bool`
+const fn operand_allowed(op: &Operator, t: &Type) -> bool {
+    use BuiltInType::*;
+    let b = match t {
+        // something else already went wrong
+        Type::Any | Type::Unknown(_) => return true,
+        Type::Pointer(_) => {
+            return matches!(
+                op,
+                Operator::Eq
+                    | Operator::NE
+                    | Operator::GT
+                    | Operator::GE
+                    | Operator::LT
+                    | Operator::LE
+            );
+        },
+        Type::BuiltIn(b) => b,
+        _ => return false,
+    };
+    let is_number = int_range(b, 64).is_some() || matches!(b, F32 | F64);
+
+    match op {
+        // floats are reported by the bitwise check already
+        Operator::And | Operator::Or | Operator::Xor | Operator::Not => {
+            is_number || matches!(b, Bool)
+        },
+        Operator::Eq | Operator::NE => is_number || matches!(b, Bool | Char),
+        Operator::GT | Operator::GE | Operator::LT | Operator::LE => is_number || matches!(b, Char),
+        _ => is_number,
+    }
+}
+
+const fn is_bitwise(op: &Operator) -> bool {
+    matches!(
+        op,
+        Operator::And | Operator::Or | Operator::Xor | Operator::LSh | Operator::RSh
+    )
+}
+
+/// Whether two function types can be constrained against each other, a call to a variadic
+/// function can have more arguments than its parameters
+const fn arity_matches(l: usize, l_variadic: bool, r: usize, r_variadic: bool) -> bool {
+    match (l_variadic, r_variadic) {
+        (false, true) => l >= r,
+        (true, false) => r >= l,
+        _ => l == r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out_of_range(src: &str) -> Vec<String> {
+        typecheck_src(src)
+            .into_iter()
+            .filter_map(|(e, _)| match e {
+                TypeCheckError::IntegerOutOfRange { typ, .. } => Some(typ),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn integer_literal_range() {
+        assert_eq!(out_of_range("fn f() { let x u8 = 300 }"), ["u8"]);
+        assert_eq!(out_of_range("fn f() { let x i8 = -129 }"), ["i8"]);
+        assert!(out_of_range("fn f() { let x u8 = 255\n let y i8 = -128 }").is_empty());
+    }
+
+    #[test]
+    fn pointer_sized_literal_range() {
+        let src = "fn f() { let x int = 5000000000 }";
+        let ((ast, count), _) = numerate_src(src);
+        assert!(typecheck(&ast, count, 64).1.is_empty());
+        assert!(matches!(
+            &typecheck(&ast, count, 32).1[..],
+            [(TypeCheckError::IntegerOutOfRange { .. }, _)]
+        ));
+    }
+
+    #[test]
+    fn non_exhaustive_match() {
+        let errs = typecheck_src(
+            "enum E { A, B(i32), C }
+            fn f(e E) {
+                match e {
+                    E::B(x) => {}
+                }
+            }",
+        );
+        assert!(matches!(
+            &errs[..],
+            [(TypeCheckError::NonExhaustiveMatch(missing), _)] if missing == &["E::A", "E::C"]
+        ));
+
+        let errs = typecheck_src(
+            "enum E { A, B(i32), C }
+            fn f(e E) {
+                match e {
+                    E::A => {}
+                    _ => {}
+                }
+            }",
+        );
+        assert!(errs.is_empty());
+    }
+
+    #[test]
+    fn unreachable_arms() {
+        let src = "enum E { A, B }
+            fn f(e E) {
+                match e {
+                    E::A => {}
+                    E::A => {}
+                    x => {}
+                    E::B => {}
+                }
+            }";
+        let errs = typecheck_src(src);
+        let arms: Vec<_> = errs
+            .iter()
+            .map(|e| match &e.0 {
+                TypeCheckError::UnreachableArm { because } => {
+                    (&src[because.start..because.end], &src[e.1.start..e.1.end])
+                },
+                e => panic!("unexpected {e:?}"),
+            })
+            .collect();
+        assert_eq!(
+            arms,
+            [("E::A => {}", "E::A => {}"), ("x => {}", "E::B => {}")]
+        );
+    }
+
+    #[test]
+    fn discriminants() {
+        let errs = typecheck_src("enum E { A = -2147483648, B, C = 2147483647, D = 2147483648 }");
+        assert!(matches!(
+            &errs[..],
+            [(TypeCheckError::IntegerOutOfRange { typ, .. }, _)] if typ == "i32"
+        ));
+        assert!(typecheck_src("enum E { A = -1, B, C = -3 }").is_empty());
+    }
+
+    #[test]
+    fn recursive_enum() {
+        let errs = typecheck_src("enum List { Nil, Cons(i32, List) }");
+        assert!(matches!(
+            &errs[..],
+            [(TypeCheckError::RecursiveType(name), _)] if name == "List"
+        ));
+        assert!(typecheck_src("enum List { Nil, Cons(i32, &List) }").is_empty());
+    }
+
+    #[test]
+    fn invalid_casts() {
+        let errs = typecheck_src(
+            "struct P { x int }
+            fn f() {
+                let a = 1 as bool
+                let b = P { x: 1 } as int
+                let c = 1.0 as &u8
+                let d = \"s\" as int
+            }",
+        );
+        let casts: Vec<_> = errs
+            .iter()
+            .map(|e| match &e.0 {
+                TypeCheckError::InvalidCast { from, to } => (from.as_str(), to.as_str()),
+                e => panic!("unexpected {e:?}"),
+            })
+            .collect();
+        assert_eq!(
+            casts,
+            [
+                ("int", "bool"),
+                ("P", "int"),
+                ("f64", "&u8"),
+                ("str", "int")
+            ]
+        );
+
+        let errs = typecheck_src(
+            "fn f(p &i32) {
+                let a = -5 as u8
+                let b = 3.5 as i32
+                let c = p as uint as &u8
+                let d = true as u8
+                let e = 97u8 as char
+            }",
+        );
+        assert!(errs.is_empty(), "{errs:?}");
+    }
+
+    #[test]
+    fn enum_casts() {
+        let errs = typecheck_src(
+            "enum Color { Red = 1, Green, Blue = 4 }
+            enum Shape { Dot, Circle(f64) }
+            fn f(c Color, s Shape) {
+                let a = c as i32
+                let b = Color::Green as u8
+                let d = s as i32
+                let e = c as f64
+            }",
+        );
+        let casts: Vec<_> = errs
+            .iter()
+            .map(|e| match &e.0 {
+                TypeCheckError::InvalidCast { from, to } => (from.as_str(), to.as_str()),
+                e => panic!("unexpected {e:?}"),
+            })
+            .collect();
+        assert_eq!(casts, [("Shape", "i32"), ("Color", "f64")]);
+    }
+
+    #[test]
+    fn array_too_large() {
+        let src = "struct S { a [u8 * 4294967296] }
+            fn f(s S) {
+                let b = [0u8 * 4294967297]
+                let c [u8 * 4294967295] = [0u8 * 4294967295]
+            }";
+        let errs = typecheck_src(src);
+        let lengths: Vec<_> = errs
+            .iter()
+            .map(|e| match &e.0 {
+                TypeCheckError::ArrayTooLarge(len) => (*len, &src[e.1.start..e.1.end]),
+                e => panic!("unexpected {e:?}"),
+            })
+            .collect();
+        assert_eq!(
+            lengths,
+            [(4294967296, "4294967296"), (4294967297, "4294967297")]
+        );
+    }
+
+    #[test]
+    fn array_length_mismatch() {
+        let src = "fn f() {
+                let a [i32 * 3] = [1, 2]
+                let b [u8 * 4] = [0u8 * 16]
+                let c [i32 * 2] = [1, 2]
+            }";
+        let errs = typecheck_src(src);
+        let markers: Vec<_> = errs
+            .iter()
+            .map(|(e, span)| {
+                assert!(matches!(e, TypeCheckError::ArrayLengthMismatch { .. }));
+                e.markers(span.clone())
+                    .into_iter()
+                    .map(|m| (m.message, &src[m.span.start..m.span.end]))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            markers,
+            [
+                [
+                    ("expected 3 elements because of this".to_string(), "3"),
+                    ("but found 2 here".to_string(), "[1, 2]"),
+                ],
+                [
+                    ("expected 4 elements because of this".to_string(), "4"),
+                    ("but found 16 here".to_string(), "16"),
+                ],
+            ]
+        );
+    }
+}
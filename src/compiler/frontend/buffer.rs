@@ -41,16 +41,23 @@ impl<A> Buffer<A> {
 }
 
 use super::*;
-pub fn to_atoken_buf<'a, A: Logos<'a> + std::fmt::Debug>(
+/// Lexes the whole source of the file with the given id
+pub fn to_atoken_buf<'a, A: Logos<'a, Error = LexerError> + std::fmt::Debug>(
     lex: &'a mut Lexer<'a, A>,
+    file: usize,
 ) -> (Buffer<(A, Span)>, Vec<AError<LexerError>>) {
     let mut buf = Buffer::empty();
     let mut err = Vec::new();
 
     while let Some(t) = lex.next() {
+        let span = Span {
+            start: lex.span().start,
+            end: lex.span().end,
+            file,
+        };
         t.map_or_else(
-            |_| err.push((LexerError, lex.span())),
-            |t| buf.push((t, lex.span())),
+            |e| err.push((e, span.clone())),
+            |t| buf.push((t, span.clone())),
         )
     }
 
@@ -1,17 +1,36 @@
 use super::*;
-pub use logos::*;
+pub use logos::{Lexer, Logos, Source};
 use std::fmt;
+use std::num::IntErrorKind;
 
 #[derive(Debug, Clone, Logos)]
 #[logos(skip r"\s")]
 #[logos(skip r"//[^\n]*")]
 #[logos(skip r"[^\\]?\\\n")]
 #[logos(skip r"/\*([^*]|\*[^/])*\*/")]
+#[logos(error = LexerError)]
 pub enum Token {
-    #[regex(r"([\d][\d_]*|0x[\da-fA-F_]+|0b[01_]+)", priority = 10, callback = parse_int)]
-    Integer(u128),
+    #[regex(
+        r"([\d][\d_]*|0x[\da-fA-F_]+|0b[01_]+|0o[0-7_]+)([iu](8|16|32|64|128)|int|uint)?",
+        priority = 10,
+        callback = parse_int
+    )]
+    Integer((u128, Option<BuiltInType>)),
+    #[regex(r"[\d][\d_]*(\.[\d][\d_]*)?[eE][+-]?[\d_]+", callback = parse_float)]
+    #[regex(r"[\d][\d_]*\.[\d][\d_]*", callback = parse_float)]
+    #[regex(r"0x[\da-fA-F_]+(\.[\da-fA-F_]*)?[pP][+-]?[\d_]+", callback = parse_float)]
+    Float(f64),
+    #[token("true", |_| true)]
+    #[token("false", |_| false)]
+    Bool(bool),
     #[regex(r"[_a-zA-Z0-9\u0100-\x{fffff}]+", priority = 2)]
     Ident,
+    #[regex(r#""([^"\\]|\\.)*"?"#, callback = parse_string)]
+    Str(String),
+    #[regex(r"'(\\[^\n][^'\s]*|[^'\\\n][_a-zA-Z0-9\u0100-\x{fffff}]*)?'", callback = parse_char)]
+    Char(char),
+    #[regex(r"'[_a-zA-Z\u0100-\x{fffff}][_a-zA-Z0-9\u0100-\x{fffff}]*")]
+    Label,
 
     #[token(";", callback = |_| false)]
     Semicolon(bool),
@@ -33,20 +52,37 @@ pub enum Token {
 
     #[regex(r"(\+|\-|\*|/|%|&|\||\^|<<|>>)(=)?", callback = parse_operator)]
     #[regex(r"(<|>|!|==|!=|<=|>=|&&|\|\||=)", callback = parse_operator)]
+    #[token("as", |_| Operator::As)]
     Operator(Operator),
 
+    #[token("=>")]
+    FatArrow,
     #[token("::")]
     ModSep,
+    #[token(":")]
+    Colon,
     #[token(".")]
     Of,
+    #[token("..")]
+    Range,
+    #[token("...")]
+    Ellipsis,
 
     #[token("let")]
     Let,
 
     #[token("fn")]
     Fn,
+    #[token("struct")]
+    Struct,
+    #[token("enum")]
+    Enum,
     #[token("return")]
     Return,
+    #[token("mod")]
+    Mod,
+    #[token("use")]
+    Use,
 
     #[token("pub")]
     Pub,
@@ -60,9 +96,13 @@ pub enum Token {
     If,
     #[token("else")]
     Else,
+    #[token("match")]
+    Match,
 
     #[token("for")]
     For,
+    #[token("in")]
+    In,
     #[token("while")]
     While,
     #[token("break")]
@@ -83,7 +123,12 @@ impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Integer(_) => write!(f, "integer"),
+            Self::Float(_) => write!(f, "float"),
+            Self::Bool(b) => write!(f, "`{b}`"),
             Self::Ident => write!(f, "identifier"),
+            Self::Str(_) => write!(f, "string literal"),
+            Self::Char(_) => write!(f, "character literal"),
+            Self::Label => write!(f, "label"),
             Self::Semicolon(true) => write!(f, "newline"),
             Self::Semicolon(false) => write!(f, "semicolon"),
             Self::RoBracketS => write!(f, "start of round bracket"),
@@ -93,8 +138,12 @@ impl fmt::Display for Token {
             Self::CuBracketS => write!(f, "start of curly bracket"),
             Self::CuBracketE => write!(f, "end of curly bracket"),
             Self::Operator(_) => write!(f, "operator"),
+            Self::FatArrow => write!(f, "`=>`"),
             Self::ModSep => write!(f, "module qualifier"),
+            Self::Colon => write!(f, "colon"),
             Self::Of => write!(f, "dot"),
+            Self::Range => write!(f, "range operator"),
+            Self::Ellipsis => write!(f, "ellipsis"),
             Self::Comma | Self::NewLine | Self::None => {
                 write!(f, "{}", format!("{self:?}").to_lowercase())
             },
@@ -131,6 +180,9 @@ pub enum Operator {
     LSh,
     RSh,
 
+    /// `expr as type`, parsed into a cast rather than a binary operation
+    As,
+
     // unary
     Plus,
     Minus,
@@ -142,6 +194,7 @@ impl Operator {
     pub const fn percedence(&self) -> usize {
         match self {
             Self::Plus | Self::Minus | Self::Deref | Self::Ref | Self::Not | Self::Index => 15,
+            Self::As => 13,
             Self::Mlt | Self::Div | Self::Mod => 12,
             Self::Add | Self::Sub => 11,
             Self::LSh | Self::RSh => 10,
@@ -189,6 +242,35 @@ impl Operator {
         )
     }
 
+    /// How the operator is written, compound assignments are written as their operator
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Add | Self::Plus => "+",
+            Self::Sub | Self::Minus => "-",
+            Self::Mlt | Self::Deref => "*",
+            Self::Div => "/",
+            Self::Mod => "%",
+            Self::Assign => "=",
+            Self::OpAssign(op) => op.symbol(),
+            Self::Index => "[]",
+            Self::Eq => "==",
+            Self::NE => "!=",
+            Self::GT => ">",
+            Self::GE => ">=",
+            Self::LT => "<",
+            Self::LE => "<=",
+            Self::And | Self::Ref => "&",
+            Self::Or => "|",
+            Self::Xor => "^",
+            Self::Not => "!",
+            Self::AndAnd => "&&",
+            Self::OrOr => "||",
+            Self::LSh => "<<",
+            Self::RSh => ">>",
+            Self::As => "as",
+        }
+    }
+
     pub const fn break_down(&self) -> Option<&'static [Self]> {
         match self {
             Self::AndAnd => Some(&[Self::And, Self::And]),
@@ -198,14 +280,133 @@ impl Operator {
     }
 }
 
-fn parse_int(lex: &Lexer<Token>) -> u128 {
+fn parse_int(lex: &Lexer<Token>) -> Result<(u128, Option<BuiltInType>), LexerError> {
     let s = lex.slice().replace('_', "");
-    match s.chars().nth(1).unwrap_or(' ') {
+
+    // `i` and `u` can't be digits, so they always start the suffix
+    let (s, suffix) =
+        s.find(['i', 'u'])
+            .map_or((s.as_str(), None), |i| match Type::from_str(&s[i..]) {
+                Type::BuiltIn(b) => (&s[..i], Some(b)),
+                _ => unreachable!(),
+            });
+
+    let value = match s.chars().nth(1).unwrap_or(' ') {
         'x' => u128::from_str_radix(&s[2..], 16),
         'b' => u128::from_str_radix(&s[2..], 2),
+        'o' => u128::from_str_radix(&s[2..], 8),
         _ => s.parse(),
     }
-    .unwrap()
+    .map_err(|e| match e.kind() {
+        // `0x_` and friends have no digits left once the underscores are removed
+        IntErrorKind::Empty => LexerError::MissingDigits,
+        _ => LexerError::IntegerOverflow,
+    })?;
+
+    Ok((value, suffix))
+}
+
+fn parse_float(lex: &Lexer<Token>) -> Result<f64, LexerError> {
+    let s = lex.slice().replace('_', "");
+    let Some(hex) = s.strip_prefix("0x") else {
+        // the exponent can consist of only underscores, which leaves it without digits
+        return s.parse().map_err(|_| LexerError::MissingExponent);
+    };
+
+    // hexadecimal floats are `<mantissa>p<binary exponent>`, which rust can't parse itself
+    let (mantissa, exp) = hex.split_once(['p', 'P']).unwrap();
+    if !exp.contains(|c: char| c.is_ascii_digit()) {
+        return Err(LexerError::MissingExponent);
+    }
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let mantissa = int.chars().chain(frac.chars()).fold(0f64, |acc, c| {
+        acc.mul_add(16.0, c.to_digit(16).unwrap() as f64)
+    });
+    let exp = exp
+        .parse::<i32>()
+        .ok()
+        .and_then(|e| e.checked_sub(i32::try_from(frac.len()).ok()?.checked_mul(4)?))
+        .ok_or(LexerError::ExponentOverflow)?;
+
+    Ok(mantissa * 2f64.powi(exp))
+}
+
+fn parse_string(lex: &Lexer<Token>) -> Result<String, LexerError> {
+    let mut chars = lex.slice()[1..].chars();
+    let mut s = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Ok(s),
+            '\\' => s.push(parse_escape(&mut chars)?),
+            c => s.push(c),
+        }
+    }
+
+    Err(LexerError::UnterminatedString)
+}
+
+fn parse_char(lex: &Lexer<Token>) -> Result<char, LexerError> {
+    let s = lex.slice();
+    let mut chars = s[1..s.len() - 1].chars();
+
+    let c = match chars.next() {
+        Some('\\') => parse_escape(&mut chars)?,
+        Some(c) => c,
+        None => return Err(LexerError::InvalidChar),
+    };
+
+    // a single character might still be made out of multiple unicode scalars
+    if chars.next().is_some() {
+        return Err(LexerError::InvalidChar);
+    }
+
+    Ok(c)
+}
+
+/// Parses an escape sequence after the backslash
+fn parse_escape(chars: &mut std::str::Chars) -> Result<char, LexerError> {
+    match chars.next() {
+        Some('n') => Ok('\n'),
+        Some('r') => Ok('\r'),
+        Some('t') => Ok('\t'),
+        Some('0') => Ok('\0'),
+        Some(c @ ('\\' | '"' | '\'')) => Ok(c),
+        Some('x') => {
+            let hex = chars.as_str().get(..2).ok_or(LexerError::InvalidEscape)?;
+            let c = u8::from_str_radix(hex, 16).map_err(|_| LexerError::InvalidEscape)?;
+            chars.nth(1);
+
+            // anything above is not a whole character in utf-8
+            if c > 0x7f {
+                return Err(LexerError::InvalidEscape);
+            }
+
+            Ok(c as char)
+        },
+        Some('u') => {
+            if chars.next() != Some('{') {
+                return Err(LexerError::InvalidEscape);
+            }
+
+            let rest = chars.as_str();
+            let len = rest.find('}').ok_or(LexerError::InvalidEscape)?;
+            let hex = rest[..len].replace('_', "");
+            if hex.is_empty() || hex.len() > 6 {
+                return Err(LexerError::InvalidEscape);
+            }
+
+            let c = u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(LexerError::InvalidEscape)?;
+            chars.nth(len);
+
+            Ok(c)
+        },
+        None => Err(LexerError::UnterminatedString),
+        _ => Err(LexerError::InvalidEscape),
+    }
 }
 
 fn parse_operator(lex: &Lexer<Token>) -> Operator {
@@ -252,3 +453,63 @@ fn _parse_oper(s: &str) -> Operator {
 }
 
 pub type AToken = Annotated<Token>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(src: &str) -> Vec<Result<Token, LexerError>> { Token::lexer(src).collect() }
+
+    #[test]
+    fn integer_overflow() {
+        assert!(matches!(
+            lex("340282366920938463463374607431768211455")[..],
+            [Ok(Token::Integer((u128::MAX, None)))]
+        ));
+        assert!(matches!(
+            lex("340282366920938463463374607431768211456")[..],
+            [Err(LexerError::IntegerOverflow)]
+        ));
+        assert!(matches!(
+            lex("0x1_0000_0000_0000_0000_0000_0000_0000_0000")[..],
+            [Err(LexerError::IntegerOverflow)]
+        ));
+    }
+
+    #[test]
+    fn missing_digits() {
+        for src in ["0x_", "0b_", "0o__", "0x_u8"] {
+            assert!(
+                matches!(lex(src)[..], [Err(LexerError::MissingDigits)]),
+                "{src}"
+            );
+        }
+        assert!(matches!(lex("0b1_0")[..], [Ok(Token::Integer((2, None)))]));
+    }
+
+    #[test]
+    fn exponent_overflow() {
+        assert!(matches!(lex("0x1p-3")[..], [Ok(Token::Float(f))] if f == 0.125));
+        assert!(matches!(
+            lex("0x1p99999999999")[..],
+            [Err(LexerError::ExponentOverflow)]
+        ));
+    }
+
+    #[test]
+    fn missing_exponent() {
+        assert!(matches!(
+            lex("1e-_")[..],
+            [Err(LexerError::MissingExponent)]
+        ));
+        assert!(matches!(
+            lex("1e+_")[..],
+            [Err(LexerError::MissingExponent)]
+        ));
+        assert!(matches!(
+            lex("0x1p_")[..],
+            [Err(LexerError::MissingExponent)]
+        ));
+        assert!(matches!(lex("1e1_0")[..], [Ok(Token::Float(f))] if f == 1e10));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod lexer;
 pub use lexer::*;
+pub mod source;
+pub use source::*;
 pub mod buffer;
 pub use buffer::*;
 pub mod preprocess;
@@ -9,7 +9,12 @@ pub fn preprocess(buf: &mut Buffer<AToken>) -> (Buffer<AToken>, Vec<AError<Lexer
             if matches!(
                 last,
                 Token::Ident
+                    | Token::Label
                     | Token::Integer(_)
+                    | Token::Float(_)
+                    | Token::Bool(_)
+                    | Token::Str(_)
+                    | Token::Char(_)
                     | Token::Break
                     | Token::Continue
                     | Token::Return
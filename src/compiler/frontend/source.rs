@@ -0,0 +1,46 @@
+/// A byte range in one of the files of a [`SourceMap`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    /// The index of the file in the [`SourceMap`]
+    pub file: usize,
+}
+
+impl Span {
+    pub const fn range(&self) -> std::ops::Range<usize> { self.start..self.end }
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub name: String,
+    pub src: String,
+    /// Byte offsets where each line starts, followed by the end of the source
+    pub lines: Vec<usize>,
+}
+
+/// Every file read during a compilation, spans refer to them by their index
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// Adds a file and returns its id
+    pub fn add(&mut self, name: String, src: String) -> usize {
+        let mut lines = Vec::with_capacity(src.matches('\n').count() + 2);
+        let mut j = 0;
+        for el in src.split('\n') {
+            lines.push(j);
+            j += el.len() + 1;
+        }
+        lines.push(j - 1);
+
+        self.files.push(SourceFile { name, src, lines });
+        self.files.len() - 1
+    }
+
+    pub fn get(&self, file: usize) -> &SourceFile { &self.files[file] }
+
+    pub fn files(&self) -> &[SourceFile] { &self.files }
+}
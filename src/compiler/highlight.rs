@@ -1,11 +1,20 @@
-pub use logos::*;
+pub use logos::Logos;
 
 #[derive(Debug, Clone, Logos)]
 pub enum HighlightToken {
-    #[regex(r"([\d][\d_]*|0x[\da-fA-F_]+|0b[01_]+)", priority = 11)]
-    Integer,
+    #[regex(
+        r"([\d][\d_]*|0x[\da-fA-F_]+|0b[01_]+|0o[0-7_]+)([iu](8|16|32|64|128)|int|uint)?",
+        priority = 11
+    )]
+    #[regex(r"[\d][\d_]*(\.[\d][\d_]*)?[eE][+-]?[\d_]+", priority = 11)]
+    #[regex(r"[\d][\d_]*\.[\d][\d_]*", priority = 11)]
+    #[regex(r"0x[\da-fA-F_]+(\.[\da-fA-F_]*)?[pP][+-]?[\d_]+", priority = 11)]
+    Number,
     #[regex(r"[_a-zA-Z0-9\u0100-\x{fffff}]+", priority = 0)]
     Ident,
+    #[regex(r#""([^"\\]|\\.)*"?"#)]
+    #[regex(r"'(\\[^\n][^'\s]*|[^'\\\n][_a-zA-Z0-9\u0100-\x{fffff}]*)?'")]
+    Literal,
 
     #[token("bool")]
     #[token("char")]
@@ -37,27 +46,41 @@ pub enum HighlightToken {
 
     #[regex(r"(\+|\-|\*|/|%|&|\||\^|<<|>>)(=)?", priority = 3)]
     #[regex(r"(<|>|!|==|!=|<=|>=|&&|\|\||=)", priority = 3)]
+    #[token("..")]
+    #[token("=>")]
     Operator,
 
     #[token("let")]
     #[token("fn")]
+    #[token("struct")]
+    #[token("enum")]
     #[token("return")]
+    #[token("mod")]
+    #[token("use")]
     #[token("pub")]
     #[token("extern")]
     #[token("static")]
     #[token("if")]
     #[token("else")]
+    #[token("match")]
     #[token("for")]
+    #[token("in")]
+    #[token("as")]
     #[token("while")]
     #[token("break")]
     #[token("continue")]
+    #[token("true")]
+    #[token("false")]
+    #[regex(r"'[_a-zA-Z\u0100-\x{fffff}][_a-zA-Z0-9\u0100-\x{fffff}]*")]
     Keyword,
 
     #[token(",")]
+    #[token(":")]
     Comma,
     #[token("::")]
     Scope,
     #[token(".")]
+    #[token("...")]
     Of,
 }
 
@@ -72,7 +95,8 @@ pub fn highlight(
         + match (this, next) {
             (Ok(Ident), Some(Ok(RoBracketS))) => "34",
             (Ok(Ident | RoBracketS | Brackets | Comma | Of), _) => "39",
-            (Ok(Integer | BuiltIn), _) => "33",
+            (Ok(Number | BuiltIn), _) => "33",
+            (Ok(Literal), _) => "32",
             (Ok(Keyword | Operator), _) => "35",
             (Ok(Unused | Scope), _) => "90",
             (Err(_), _) => "1;31",
@@ -6,22 +6,22 @@
     clippy::suspicious,
     clippy::style
 )]
-#![allow(
-    clippy::semicolon_inside_block,
-    clippy::just_underscores_and_digits
-)]
+#![allow(clippy::semicolon_inside_block, clippy::just_underscores_and_digits)]
 
 mod compiler;
+use compiler::backend::{link::link, llvm::*};
 use compiler::frontend::*;
 mod args;
-use args::{Args, Parser};
+use args::{Args, Command, Emit};
 mod panic;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
 fn main() {
     panic::init();
 
-    let args = Args::parse();
+    let args = Args::from_env();
 
     #[cfg(debug_assertions)]
     if let Some(msg) = args.panics {
@@ -30,12 +30,13 @@ fn main() {
 
     let filename = &args.input;
 
-    let src = std::fs::read_to_string(filename).unwrap();
+    let mut sources = SourceMap::default();
+    let file = sources.add(filename.clone(), std::fs::read_to_string(filename).unwrap());
 
     macro_rules! unwrap_or_report {
         ($i: expr) => {{
             let i = $i;
-            let (msg, err) = report(i.1, filename, &src, &args);
+            let (msg, err) = report(i.1, &sources, &args);
             print!("{}", msg);
             if err {
                 exit(1);
@@ -45,18 +46,188 @@ fn main() {
         }};
     }
 
-    let mut l = Token::lexer(&src);
-    let mut buf = unwrap_or_report!(to_atoken_buf(&mut l));
+    macro_rules! unwrap_or_fail {
+        ($i: expr) => {{
+            match $i {
+                Ok(i) => i,
+                Err(err) => {
+                    println!("{}: {err}\x1b[0m\n", Severeness::Error.as_str(&args));
+                    exit(1);
+                },
+            }
+        }};
+    }
+
+    let last = args.last_stage();
+
+    let src = &sources.get(file).src;
+    let mut l = Token::lexer(src);
+    let mut buf = unwrap_or_report!(to_atoken_buf(&mut l, file));
     let mut pp = unwrap_or_report!(preprocess(&mut buf));
+    if args.emits(Emit::Tokens) {
+        for (t, s) in pp.buf.iter() {
+            println!("{s:?}\t{t:?}");
+        }
+    }
+
+    if last < Emit::Ast {
+        return;
+    }
 
-    let ast = unwrap_or_report!(parse(&mut pp, &src));
-    println!("{ast:#?}");
+    let mut ast = unwrap_or_report!(parse(&mut pp, src, file));
+    let root = Path::new(filename);
+    let mut loading = HashSet::from([root.canonicalize().unwrap_or_else(|_| root.to_path_buf())]);
+    load_modules(&mut ast, file, &mut sources, &args, &mut loading);
+    if args.emits(Emit::Ast) {
+        println!("{ast:#?}");
+    }
+
+    if last < Emit::Nast {
+        return;
+    }
 
     let (nast, ncnt) = unwrap_or_report!(numerate(ast));
-    println!("{nast:#?}");
+    if args.emits(Emit::Nast) {
+        println!("{nast:#?}");
+    }
+
+    if last < Emit::Types {
+        return;
+    }
+
+    // `int` and `uint` are as wide as pointers of the target
+    let machine = unwrap_or_fail!(target_machine(&args));
+    let ptr_bits = machine.get_target_data().get_pointer_byte_size(None) * 8;
 
-    let types = unwrap_or_report!(typecheck(&nast, ncnt));
-    println!("{types:#?}");
+    let types = unwrap_or_report!(typecheck(&nast, ncnt, ptr_bits));
+    if args.emits(Emit::Types) {
+        println!("{types:#?}");
+    }
+
+    if last < Emit::LlvmIr {
+        return;
+    }
+
+    let context = Context::create();
+    let module = emit_code(&context, &nast, &types, &sources, &machine, &args);
+    unwrap_or_fail!(optimize(&module, &machine, args.opt_level));
+
+    if args.emits(Emit::LlvmIr) {
+        unwrap_or_fail!(write_ir(&module, &args.artifact(Emit::LlvmIr)));
+    }
+
+    if args.emits(Emit::LlvmBc) {
+        unwrap_or_fail!(write_bitcode(&module, &args.artifact(Emit::LlvmBc)));
+    }
+
+    if args.emits(Emit::Asm) {
+        unwrap_or_fail!(write_assembly(&module, &machine, &args.artifact(Emit::Asm)));
+    }
+
+    if let Some(Command::Run { args: argv, .. }) = &args.command {
+        let argv = std::iter::once(filename.as_str())
+            .chain(argv.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        exit(unwrap_or_fail!(run_jit(&module, args.opt_level, &argv)));
+    }
+
+    let object = if args.emits(Emit::Obj) {
+        let object = args.artifact(Emit::Obj);
+        unwrap_or_fail!(write_object(&module, &machine, &object));
+        Some(object)
+    } else {
+        None
+    };
+
+    if args.emits(Emit::Exe) {
+        if let Some(object) = object {
+            unwrap_or_fail!(link(&[&object], &args.artifact(Emit::Exe), &args.linker));
+        } else {
+            let object = std::env::temp_dir().join(format!("krillion-{}.o", std::process::id()));
+            unwrap_or_fail!(write_object(&module, &machine, &object));
+
+            let linked = link(&[&object], &args.artifact(Emit::Exe), &args.linker);
+            let _ = std::fs::remove_file(&object);
+            unwrap_or_fail!(linked);
+        }
+    }
+}
+
+/// Loads the bodies of `mod name` declarations from `name.kr` next to the file declaring them
+///
+/// `loading` holds the canonical paths of the files currently being loaded, so a module that
+/// ends up including itself is reported instead of being loaded forever
+fn load_modules(
+    ast: &mut UntypedAst,
+    file: usize,
+    sources: &mut SourceMap,
+    args: &Args,
+    loading: &mut HashSet<PathBuf>,
+) {
+    let dir = Path::new(&sources.get(file).name)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_path_buf();
+
+    for n in ast.iter_mut() {
+        let NodeKind::Module { ident, body, .. } = &mut n.kind else {
+            continue;
+        };
+
+        if let Some(body) = body {
+            load_modules(body, file, sources, args, loading);
+            continue;
+        }
+
+        let path = dir.join(format!("{}.kr", ident.0));
+        let Ok(src) = std::fs::read_to_string(&path) else {
+            let errs = vec![(
+                ParseError::ModuleNotFound(path.display().to_string()),
+                ident.1.clone(),
+            )];
+            report_or_exit(((), errs), sources, args);
+            continue;
+        };
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if loading.contains(&canonical) {
+            let errs = vec![(
+                ParseError::ModuleCycle(path.display().to_string()),
+                ident.1.clone(),
+            )];
+            report_or_exit(((), errs), sources, args);
+            continue;
+        }
+        let module_file = sources.add(path.to_string_lossy().to_string(), src);
+
+        let src = &sources.get(module_file).src;
+        let mut l = Token::lexer(src);
+        let mut buf = report_or_exit(to_atoken_buf(&mut l, module_file), sources, args);
+        let mut pp = report_or_exit(preprocess(&mut buf), sources, args);
+        let (module, mut errs) = parse(&mut pp, src, module_file);
+        for n in module.iter() {
+            if !n.kind.is_item() {
+                errs.push((ParseError::ExpectedItem, n.span.clone()));
+            }
+        }
+        let mut module = report_or_exit((module, errs), sources, args);
+
+        loading.insert(canonical.clone());
+        load_modules(&mut module, module_file, sources, args, loading);
+        loading.remove(&canonical);
+        *body = Some(module);
+    }
+}
+
+fn report_or_exit<T, E: CompilerError>(
+    i: (T, Vec<AError<E>>),
+    sources: &SourceMap,
+    args: &Args,
+) -> T {
+    let (msg, err) = report(i.1, sources, args);
+    print!("{msg}");
+    if err {
+        exit(1);
+    }
 
-    // compiler::backend::llvm::emit_code();
+    i.0
 }